@@ -0,0 +1,765 @@
+use super::{Builder, Cache as InnerCache, Freshness, Overweight};
+use crate::policy::{EvictionPolicy, Lru};
+use std::{
+    borrow::Borrow,
+    future::Future,
+    hash::Hash,
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{Notify, RwLock, RwLockWriteGuard},
+    task, time,
+};
+
+/// Async version of Cache with pluggable eviction strategy, LRU by default
+///
+/// Reading only takes shared lock when eviction policy doesn't track access,
+/// and entry doesn't expire after it's last read.
+///
+/// # Breaking change
+///
+/// As concurrent readers share the value behind a read lock, `V` has to be `Sync`,
+/// which 0.1 didn't require. Value which is only `Send` could be wrapped in
+/// `std::sync::Mutex`.
+pub struct Cache<K, V, P: EvictionPolicy = Lru> {
+    inner: Arc<RwLock<InnerCache<K, V, P>>>,
+    // runtime daemon evicting expired entries, spawned on the first entry which expires
+    daemon_spawned: AtomicBool,
+    // wakes the daemon when an entry expires earlier than it's waiting for,
+    // or the cache is dropped
+    wake: Arc<Notify>,
+}
+
+impl<K, V, P: EvictionPolicy> Drop for Cache<K, V, P> {
+    fn drop(&mut self) {
+        self.wake.notify();
+    }
+}
+
+#[allow(clippy::needless_doctest_main)]
+impl<K, V, P> Cache<K, V, P>
+where
+    K: 'static + Hash + Eq + Sync + Send,
+    V: 'static + Clone + Send + Sync,
+    P: 'static + EvictionPolicy + Send + Sync,
+    P::Meta: Send + Sync,
+{
+    /// Create new Cache, which will expiring its entry after `timeout_secs`
+    /// and allocating new slab with capacity `multiply_cap` when no space
+    /// is ready and no entry expires. Entry never expires for zero `timeout_secs`.
+    pub fn new(multiply_cap: usize, timeout_secs: u64) -> Arc<Self>
+    where
+        P: Default,
+    {
+        Self::with_timeout(multiply_cap, Duration::from_secs(timeout_secs))
+    }
+
+    /// Create new Cache, the same as `new`, but expiring its entry after `timeout`,
+    /// counted in milliseconds. Runtime daemon evicting expired entry, as soon as
+    /// it expires, is spawned on putting the first entry. Zero `timeout` never expires
+    /// entry, so that no daemon is spawned either, unless entry is given its own
+    /// time-to-live.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{LruAsyncCache, MockClock};
+    /// use std::time::Duration;
+    /// use tokio::{task, time};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::with_timeout(2, Duration::from_millis(100));
+    ///
+    ///     cache.put(1, "a").await;
+    ///     assert_eq!(cache.get(&1).await, Some("a"));
+    ///
+    ///     // the same cache, built with mock clock, moved forward along with
+    ///     // paused tokio's timer which runtime daemon waits on
+    ///     time::pause();
+    ///     let clock = MockClock::new();
+    ///     let cache = LruAsyncCache::builder()
+    ///         .multiply_cap(2)
+    ///         .timeout(Duration::from_millis(100))
+    ///         .clock(clock.clone())
+    ///         .build_async();
+    ///
+    ///     cache.put(1, "a").await;
+    ///     task::yield_now().await;
+    ///     clock.advance(Duration::from_millis(100));
+    ///     time::advance(Duration::from_millis(100)).await;
+    ///     task::yield_now().await;
+    ///     assert_eq!(cache.len().await, 0);
+    /// }
+    /// ```
+    pub fn with_timeout(multiply_cap: usize, timeout: Duration) -> Arc<Self>
+    where
+        P: Default,
+    {
+        Self::wrap(InnerCache::with_timeout(multiply_cap, timeout))
+    }
+
+    /// Returns a [`Builder`](struct.Builder.html) to configure new Cache,
+    /// which is created using `build_async`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::builder()
+    ///         .max_capacity(2)
+    ///         .timeout_secs(60)
+    ///         .build_async();
+    ///
+    ///     cache.put(1, "a").await;
+    ///     cache.put(2, "b").await;
+    ///     cache.put(3, "c").await;
+    ///
+    ///     assert_eq!(cache.len().await, 2);
+    ///     assert_eq!(cache.get(&1).await, None);
+    /// }
+    /// ```
+    pub fn builder() -> Builder<K, V, P>
+    where
+        P: Default,
+    {
+        Builder::new(P::default())
+    }
+
+    /// Wrap the cache, whose runtime daemon is spawned once any entry expires
+    pub(super) fn wrap(cache: InnerCache<K, V, P>) -> Arc<Self> {
+        Arc::new(Cache {
+            inner: Arc::new(RwLock::new(cache)),
+            daemon_spawned: AtomicBool::new(false),
+            wake: Arc::new(Notify::new()),
+        })
+    }
+
+    /// Take exclusive lock of the cache, waking runtime daemon once it's released
+    /// if any entry expires earlier than before.
+    async fn write(&self) -> WriteGuard<'_, K, V, P> {
+        let cache = self.inner.write().await;
+        WriteGuard {
+            next_expiry: cache.next_expiry(),
+            cache,
+            owner: self,
+        }
+    }
+
+    /// Wake runtime daemon to evict expired entry by the new earliest expiry,
+    /// spawning it if it's not yet.
+    fn wake(&self) {
+        if self.daemon_spawned.swap(true, Ordering::Relaxed) {
+            self.wake.notify();
+        } else {
+            task::spawn(evict_expired(
+                Arc::downgrade(&self.inner),
+                self.wake.clone(),
+            ));
+        }
+    }
+
+    /// Returns the clone value of the key in the cache or `None` if it is not
+    /// present in the cache. Records the access to eviction policy if it exists, e.g.
+    /// moves the key to the head of the LRU list. When the policy doesn't track access,
+    /// this is the same as `peek`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::new(2, 60);
+    ///
+    ///     assert_eq!(cache.put(String::from("1"), "a").await, None);
+    ///     assert_eq!(cache.put(String::from("2"), "b").await, None);
+    ///     assert_eq!(cache.put(String::from("2"), "c").await, Some("b"));
+    ///     assert_eq!(cache.put(String::from("3"), "d").await, None);
+    ///
+    ///     assert_eq!(cache.get(&String::from("1")).await, Some("a"));
+    ///     assert_eq!(cache.get(&String::from("2")).await, Some("c"));
+    ///     assert_eq!(cache.get(&String::from("3")).await, Some("d"));
+    /// }
+    /// ```
+    pub async fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        Arc<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !P::TRACK_ACCESS {
+            let cache = self.inner.read().await;
+            if cache.reads_shared() {
+                return cache.read(key).cloned();
+            }
+        }
+        let mut cache = self.write().await;
+        cache.get(key).cloned()
+    }
+
+    /// Returns the clone value of the key in the cache, the same as `get`, along with
+    /// whether it should be refreshed now, before it expires, see `get_with_refresh_hint`
+    /// of [`LruCache`](type.LruCache.html). This always takes exclusive lock of the cache.
+    pub async fn get_with_refresh_hint<Q>(&self, key: &Q) -> Option<(V, bool)>
+    where
+        Arc<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut cache = self.write().await;
+        cache
+            .get_with_refresh_hint(key)
+            .map(|(value, refresh)| (value.clone(), refresh))
+    }
+
+    /// Returns the clone value of the key in the cache, or the value computed by `load`
+    /// when it is not present. The value is also loaded again ahead of its expiry, with
+    /// rising probability as it nears, so that only a few callers reload it while others
+    /// are still given the cached value, instead of all of them at once on its expiry.
+    /// The time taken by `load`, measured by the clock of the cache, is recorded as its
+    /// compute time, see `put_with_compute_time`. No lock is held while loading.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::new(2, 60);
+    ///
+    ///     assert_eq!(cache.get_or_load(1, || async { "a" }).await, "a");
+    ///     assert_eq!(cache.get_or_load(1, || async { "b" }).await, "a");
+    /// }
+    /// ```
+    pub async fn get_or_load<F, Fut>(&self, key: K, load: F) -> V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        if let Some((value, false)) = self.get_with_refresh_hint(&key).await {
+            return value;
+        }
+        self.put_loaded(key, load()).await
+    }
+
+    /// Returns the clone value of the key in the cache, tagged as
+    /// [`Stale`](enum.Freshness.html) once it's expired, but still within the grace
+    /// window of `stale_while_revalidate`, see `get_or_stale` of
+    /// [`LruCache`](type.LruCache.html). This always takes exclusive lock of the cache.
+    pub async fn get_or_stale<Q>(&self, key: &Q) -> Option<Freshness<V>>
+    where
+        Arc<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut cache = self.write().await;
+        cache
+            .get_or_stale(key)
+            .map(|found| found.map(|value| value.clone()))
+    }
+
+    /// Returns the clone value of the key in the cache, or the value computed by `load`
+    /// when it is not present. Stale value within the grace window of
+    /// `stale_while_revalidate` is returned right away, while `load` reloads it in
+    /// the background. Only one reload runs for each stale entry, further calls keep
+    /// getting the stale value until it's put again. The time taken by `load` is recorded
+    /// as its compute time, the same as `get_or_load`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{Freshness, LruAsyncCache, MockClock};
+    /// use std::time::Duration;
+    /// use tokio::task;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let clock = MockClock::new();
+    ///     let cache = LruAsyncCache::builder()
+    ///         .max_capacity(2)
+    ///         .timeout(Duration::from_millis(100))
+    ///         .stale_while_revalidate(Duration::from_secs(60))
+    ///         .clock(clock.clone())
+    ///         .build_async();
+    ///
+    ///     let rate = cache.get_or_revalidate("rate", || async { 1.5 }).await;
+    ///     assert_eq!(rate, Freshness::Fresh(1.5));
+    ///
+    ///     clock.advance(Duration::from_millis(100));
+    ///     let rate = cache.get_or_revalidate("rate", || async { 1.6 }).await;
+    ///     assert_eq!(rate, Freshness::Stale(1.5));
+    ///
+    ///     // reloaded in the background, once this task yields
+    ///     task::yield_now().await;
+    ///     assert_eq!(cache.get(&"rate").await, Some(1.6));
+    /// }
+    /// ```
+    pub async fn get_or_revalidate<F, Fut>(self: &Arc<Self>, key: K, load: F) -> Freshness<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: 'static + Future<Output = V> + Send,
+    {
+        {
+            let mut cache = self.write().await;
+            let found = cache
+                .get_or_stale(&key)
+                .map(|found| found.map(|value| value.clone()));
+            match found {
+                Some(Freshness::Fresh(value)) => return Freshness::Fresh(value),
+                Some(Freshness::Stale(value)) => {
+                    if cache.revalidate(&key) {
+                        let reload = load();
+                        let cache_async = self.clone();
+                        task::spawn(async move {
+                            cache_async.put_loaded(key, reload).await;
+                        });
+                    }
+                    return Freshness::Stale(value);
+                }
+                None => {}
+            }
+        }
+        Freshness::Fresh(self.put_loaded(key, load()).await)
+    }
+
+    /// Await the value of `load`, then put it into cache along with the time it took.
+    async fn put_loaded(&self, key: K, load: impl Future<Output = V>) -> V {
+        let start = self.inner.read().await.now();
+        let value = load.await;
+        let mut cache = self.write().await;
+        let compute_time = cache.now().saturating_duration_since(start);
+        cache.put_with_compute_time(key, value.clone(), compute_time);
+        value
+    }
+
+    /// Returns the clone value of the key in the cache or `None` if it is not
+    /// present in the cache or already expired, without recording the access.
+    /// This only takes shared lock of the cache.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::builder()
+    ///         .max_capacity(2)
+    ///         .timeout_secs(60)
+    ///         .build_async();
+    ///
+    ///     cache.put(1, "a").await;
+    ///     cache.put(2, "b").await;
+    ///
+    ///     assert_eq!(cache.peek(&1).await, Some("a"));
+    ///     assert_eq!(cache.push(3, "c").await, Some((1, "a")));
+    /// }
+    /// ```
+    pub async fn peek<Q>(&self, key: &Q) -> Option<V>
+    where
+        Arc<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let cache = self.inner.read().await;
+        cache.peek(key).cloned()
+    }
+
+    /// Puts a key-value pair into cache. If the key already exists in the cache, then it updates
+    /// the key's value and returns the old value. Otherwise, `None` is returned.
+    ///
+    /// # Overweight entry
+    ///
+    /// Entry heavier than `max_weight` is dropped, returning `None` as if it's put,
+    /// and leaving the cache untouched, so that the old value of the key, if any,
+    /// is kept. Use `try_push` to tell it apart, getting the entry back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::new(2, 60);
+    ///
+    ///     assert_eq!(None, cache.put(String::from("1"), "a").await);
+    ///     assert_eq!(None, cache.put(String::from("2"), "b").await);
+    ///     assert_eq!(Some("b"), cache.put(String::from("2"), "beta").await);
+    ///
+    ///     assert_eq!(cache.get(&String::from("1")).await, Some("a"));
+    ///     assert_eq!(cache.get(&String::from("2")).await, Some("beta"));
+    /// }
+    /// ```
+    pub async fn put(&self, key: K, value: V) -> Option<V> {
+        let mut cache = self.write().await;
+        cache.put(key, value)
+    }
+
+    /// Puts a key-value pair into cache, the same as `put`, but the entry expires after
+    /// given `ttl` instead of the timeout of the cache, see [`ExpiryMode`](enum.ExpiryMode.html).
+    /// Reading the entry refreshes it with the same `ttl` when the cache expires entry after
+    /// access, while putting it again without `ttl` restores the timeout of the cache.
+    /// Note that expired entry is removed by runtime daemon as soon as it expires,
+    /// even when the cache itself never expires entry.
+    ///
+    /// # Overweight entry
+    ///
+    /// Entry heavier than `max_weight` is dropped, returning `None`,
+    /// and keeping the old value of the key, if any, the same as `put`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{LruAsyncCache, MockClock};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let clock = MockClock::new();
+    ///     let cache = LruAsyncCache::builder()
+    ///         .multiply_cap(2)
+    ///         .timeout_secs(60)
+    ///         .clock(clock.clone())
+    ///         .build_async();
+    ///
+    ///     cache.put_with_ttl(1, "a", Duration::from_secs(1)).await;
+    ///     cache.put(2, "b").await;
+    ///
+    ///     clock.advance(Duration::from_secs(1));
+    ///     assert_eq!(cache.get(&1).await, None);
+    ///     assert_eq!(cache.get(&2).await, Some("b"));
+    /// }
+    /// ```
+    pub async fn put_with_ttl(&self, key: K, value: V, ttl: Duration) -> Option<V> {
+        let mut cache = self.write().await;
+        cache.put_with_ttl(key, value, ttl)
+    }
+
+    /// Puts a key-value pair into cache, the same as `put`, but the entry expires at given
+    /// `deadline`, measured by the clock of the cache, regardless of its timeout. Note that
+    /// expired entry is removed by runtime daemon as soon as it expires.
+    ///
+    /// # Overweight entry
+    ///
+    /// Entry heavier than `max_weight` is dropped, returning `None`,
+    /// and keeping the old value of the key, if any, the same as `put`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{LruAsyncCache, MockClock, TimeSource};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let clock = MockClock::new();
+    ///     let cache = LruAsyncCache::builder()
+    ///         .multiply_cap(2)
+    ///         .timeout_secs(60)
+    ///         .clock(clock.clone())
+    ///         .build_async();
+    ///
+    ///     cache
+    ///         .put_until(1, "a", clock.now() + Duration::from_millis(100))
+    ///         .await;
+    ///     cache.put(2, "b").await;
+    ///
+    ///     clock.advance(Duration::from_millis(100));
+    ///     assert_eq!(cache.get(&1).await, None);
+    ///     assert_eq!(cache.get(&2).await, Some("b"));
+    /// }
+    /// ```
+    pub async fn put_until(&self, key: K, value: V, deadline: Instant) -> Option<V> {
+        let mut cache = self.write().await;
+        cache.put_until(key, value, deadline)
+    }
+
+    /// Puts a key-value pair into cache, the same as `put`, along with the `compute_time`
+    /// it took to compute the value, see `put_with_compute_time` of
+    /// [`LruCache`](type.LruCache.html).
+    ///
+    /// # Overweight entry
+    ///
+    /// Entry heavier than `max_weight` is dropped, returning `None`,
+    /// and keeping the old value of the key, if any, the same as `put`.
+    pub async fn put_with_compute_time(
+        &self,
+        key: K,
+        value: V,
+        compute_time: Duration,
+    ) -> Option<V> {
+        let mut cache = self.write().await;
+        cache.put_with_compute_time(key, value, compute_time)
+    }
+
+    /// Puts a key-value pair into cache. If the key already exists in the cache, then it updates
+    /// the key's value and returns the given key with old value. If an entry is removed to
+    /// accomodate new entry, either because it expires or the cache is full, then it returns
+    /// the removed key-value pair. Otherwise, `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::builder()
+    ///         .max_capacity(2)
+    ///         .timeout_secs(60)
+    ///         .build_async();
+    ///
+    ///     assert_eq!(None, cache.push(1, "a").await);
+    ///     assert_eq!(None, cache.push(2, "b").await);
+    ///     assert_eq!(Some((2, "b")), cache.push(2, "beta").await);
+    ///     assert_eq!(Some((1, "a")), cache.push(3, "c").await);
+    /// }
+    /// ```
+    pub async fn push(&self, key: K, value: V) -> Option<(K, V)> {
+        let mut cache = self.write().await;
+        cache.push(key, value)
+    }
+
+    /// Puts a key-value pair into cache, the same as `push`, but returns every removed
+    /// key-value pair, starting with the given key with old value if it already exists.
+    /// More than one entry could be removed to make room for new entry when the cache is
+    /// built with `max_weight`. Entry heavier than `max_weight` is rejected, leaving the
+    /// cache untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{policy::Weight, LruAsyncCache, Overweight};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::builder()
+    ///         .multiply_cap(4)
+    ///         .max_weight(10)
+    ///         .weigher(|_: &usize, value: &&str| Weight {
+    ///             cost: 1,
+    ///             size: value.len(),
+    ///         })
+    ///         .timeout_secs(60)
+    ///         .build_async();
+    ///
+    ///     assert_eq!(cache.try_push(1, "aaaa").await, Ok(vec![]));
+    ///     assert_eq!(cache.try_push(2, "bbbb").await, Ok(vec![]));
+    ///     assert_eq!(
+    ///         cache.try_push(3, "cccccccc").await,
+    ///         Ok(vec![(1, "aaaa"), (2, "bbbb")])
+    ///     );
+    ///     assert_eq!(
+    ///         cache.try_push(4, "dddddddddddd").await,
+    ///         Err(Overweight {
+    ///             key: 4,
+    ///             value: "dddddddddddd"
+    ///         })
+    ///     );
+    ///     assert_eq!(cache.weight().await, 8);
+    /// }
+    /// ```
+    pub async fn try_push(&self, key: K, value: V) -> Result<Vec<(K, V)>, Overweight<K, V>> {
+        let mut cache = self.write().await;
+        cache.try_push(key, value)
+    }
+
+    /// Returns the maximum number of key-value pairs the cache can hold.
+    /// Note that on data insertion, when no space is available and no
+    /// entry is timeout, then capacity will be added with `multiply_cap`
+    /// to accomodate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::new(2, 60);
+    ///     assert_eq!(cache.capacity().await, 2);
+    ///
+    ///     cache.put(1, "a").await;
+    ///     assert_eq!(cache.capacity().await, 2);
+    ///
+    ///     cache.put(2, "b").await;
+    ///     assert_eq!(cache.capacity().await, 2);
+    ///
+    ///     cache.put(3, "c").await;
+    ///     assert_eq!(cache.capacity().await, 4);
+    /// }
+    /// ```
+    pub async fn capacity(&self) -> usize {
+        let cache = self.inner.read().await;
+        cache.capacity()
+    }
+
+    /// Returns the total weight of entries in the cache, given by weigher's size,
+    /// or the number of entries without weigher.
+    pub async fn weight(&self) -> usize {
+        let cache = self.inner.read().await;
+        cache.weight()
+    }
+
+    /// Returns the number of key-value pairs that are currently in the the cache.
+    /// Note that len should be less than or equal to capacity
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::new(2, 60);
+    ///     assert_eq!(cache.len().await, 0);
+    ///
+    ///     cache.put(1, "a").await;
+    ///     assert_eq!(cache.len().await, 1);
+    ///
+    ///     cache.put(2, "b").await;
+    ///     assert_eq!(cache.len().await, 2);
+    ///     assert_eq!(cache.capacity().await, 2);
+    ///
+    ///     cache.put(3, "c").await;
+    ///     assert_eq!(cache.len().await, 3);
+    ///     assert_eq!(cache.capacity().await, 4);
+    /// }
+    /// ```
+    pub async fn len(&self) -> usize {
+        let cache = self.inner.read().await;
+        cache.len()
+    }
+
+    /// Returns a bool indicating whether the cache is empty or not.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::new(2, 60);
+    ///     assert!(cache.is_empty().await);
+    ///
+    ///     cache.put(String::from("1"), "a").await;
+    ///     assert!(!cache.is_empty().await);
+    /// }
+    /// ```
+    pub async fn is_empty(&self) -> bool {
+        let cache = self.inner.read().await;
+        cache.is_empty()
+    }
+}
+
+/// Exclusive lock of the cache, which wakes runtime daemon on release,
+/// see `Cache::write`.
+struct WriteGuard<'a, K, V, P>
+where
+    K: 'static + Hash + Eq + Sync + Send,
+    V: 'static + Clone + Send + Sync,
+    P: 'static + EvictionPolicy + Send + Sync,
+    P::Meta: Send + Sync,
+{
+    cache: RwLockWriteGuard<'a, InnerCache<K, V, P>>,
+    owner: &'a Cache<K, V, P>,
+    // before the lock is taken
+    next_expiry: Option<Instant>,
+}
+
+impl<K, V, P> Deref for WriteGuard<'_, K, V, P>
+where
+    K: 'static + Hash + Eq + Sync + Send,
+    V: 'static + Clone + Send + Sync,
+    P: 'static + EvictionPolicy + Send + Sync,
+    P::Meta: Send + Sync,
+{
+    type Target = InnerCache<K, V, P>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cache
+    }
+}
+
+impl<K, V, P> DerefMut for WriteGuard<'_, K, V, P>
+where
+    K: 'static + Hash + Eq + Sync + Send,
+    V: 'static + Clone + Send + Sync,
+    P: 'static + EvictionPolicy + Send + Sync,
+    P::Meta: Send + Sync,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cache
+    }
+}
+
+impl<K, V, P> Drop for WriteGuard<'_, K, V, P>
+where
+    K: 'static + Hash + Eq + Sync + Send,
+    V: 'static + Clone + Send + Sync,
+    P: 'static + EvictionPolicy + Send + Sync,
+    P::Meta: Send + Sync,
+{
+    fn drop(&mut self) {
+        let before = self.next_expiry;
+        let expires_earlier = self
+            .cache
+            .next_expiry()
+            .map_or(false, |after| before.map_or(true, |before| after < before));
+        if expires_earlier {
+            self.owner.wake();
+        }
+    }
+}
+
+/// Runtime daemon evicting expired entries as soon as the earliest of them expires,
+/// sleeping on tokio's timer meanwhile, until the cache is dropped.
+async fn evict_expired<K, V, P>(cache: Weak<RwLock<InnerCache<K, V, P>>>, wake: Arc<Notify>)
+where
+    K: 'static + Hash + Eq + Sync + Send,
+    V: 'static + Clone + Send + Sync,
+    P: 'static + EvictionPolicy + Send + Sync,
+    P::Meta: Send + Sync,
+{
+    loop {
+        let delay = match cache.upgrade() {
+            Some(cache) => {
+                let mut cache = cache.write().await;
+                cache.evict();
+                cache
+                    .next_expiry()
+                    .map(|expiry| expiry.saturating_duration_since(cache.now()))
+            }
+            None => return,
+        };
+        match delay {
+            Some(delay) => {
+                let _ = time::timeout(delay, wake.notified()).await;
+            }
+            None => wake.notified().await,
+        }
+    }
+}
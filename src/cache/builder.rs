@@ -0,0 +1,324 @@
+#[cfg(feature = "asynchronous")]
+use super::asynchronous::Cache as AsyncCache;
+use super::{
+    BoxClock, BoxExpiry, BoxWeigher, Cache, EarlyRefresh, Expiry, ExpiryMode, Jitter,
+    MonotonicClock, Random, Storage, TimeSource,
+};
+use crate::policy::{EvictionPolicy, Lru, Weigher};
+#[cfg(feature = "asynchronous")]
+use std::sync::Arc;
+use std::{collections::HashMap, hash::Hash, marker::PhantomData, time::Duration};
+
+/// Builder of Cache with pluggable eviction strategy, LRU by default
+///
+/// Capacity should be set with either `max_capacity`, to hold at most
+/// given number of entries and evict the victim chosen by policy when full,
+/// or `multiply_cap`, to allocate new slab with given capacity when full
+/// and no entry expires. Total weight of entries could be bounded as well
+/// with `max_weight`. Entry never expires unless timeout is set, e.g. with
+/// `timeout_secs`.
+pub struct Builder<K, V, P = Lru> {
+    cap: usize,
+    grow: bool,
+    expiry: Option<ExpiryMode>,
+    policy: P,
+    weigher: Option<BoxWeigher<K, V>>,
+    max_weight: Option<usize>,
+    custom_expiry: Option<BoxExpiry<K, V>>,
+    clock: Option<BoxClock>,
+    jitter_percent: usize,
+    jitter_seed: Option<u64>,
+    refresh_beta: f64,
+    grace: Duration,
+    _marker: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V, P> Builder<K, V, P> {
+    pub(super) fn new(policy: P) -> Self {
+        Builder {
+            cap: 0,
+            grow: false,
+            expiry: None,
+            policy,
+            weigher: None,
+            max_weight: None,
+            custom_expiry: None,
+            clock: None,
+            jitter_percent: 0,
+            jitter_seed: None,
+            refresh_beta: 1.0,
+            grace: Duration::from_secs(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Hold at most `max_capacity` entries, evicting the victim chosen
+    /// by policy when full
+    pub fn max_capacity(mut self, max_capacity: usize) -> Self {
+        self.cap = max_capacity;
+        self.grow = false;
+        self
+    }
+
+    /// Allocate new slab with capacity `multiply_cap` when no space is ready
+    /// and no entry expires
+    pub fn multiply_cap(mut self, multiply_cap: usize) -> Self {
+        self.cap = multiply_cap;
+        self.grow = true;
+        self
+    }
+
+    /// Expire entry after `timeout_secs` since it's last written or read,
+    /// the same as `timeout` in whole seconds, or never for zero
+    pub fn timeout_secs(self, timeout_secs: u64) -> Self {
+        self.timeout(Duration::from_secs(timeout_secs))
+    }
+
+    /// Expire entry after `timeout`, counted in milliseconds, since it's last
+    /// written or read, the same as `expiry_mode` with `ExpireAfterAccess`,
+    /// or `Never` for zero `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.expiry = Some(ExpiryMode::timeout(timeout));
+        self
+    }
+
+    /// Expire entry according to given `expiry` mode
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{ExpiryMode, LruCache, MockClock};
+    /// use std::time::Duration;
+    ///
+    /// let clock = MockClock::new();
+    /// let mut cache = LruCache::builder()
+    ///     .max_capacity(2)
+    ///     .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(1)))
+    ///     .clock(clock.clone())
+    ///     .build();
+    ///
+    /// cache.put(1, "a");
+    /// clock.advance(Duration::from_millis(500));
+    /// assert_eq!(cache.get(&1), Some(&"a"));
+    ///
+    /// // reading doesn't refresh the entry
+    /// clock.advance(Duration::from_millis(500));
+    /// assert_eq!(cache.get(&1), None);
+    /// ```
+    pub fn expiry_mode(mut self, expiry: ExpiryMode) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    /// Expire each entry after the duration given by `expiry` on its creation, update
+    /// and read, instead of expiry mode, so that timeout set by `timeout_secs` or
+    /// `expiry_mode` is ignored. See [`Expiry`](trait.Expiry.html) for example.
+    pub fn expiry<E>(mut self, expiry: E) -> Self
+    where
+        E: 'static + Expiry<K, V> + Send + Sync,
+    {
+        self.custom_expiry = Some(Box::new(expiry));
+        self
+    }
+
+    /// Spread the duration each entry lives, stamped when it's written, randomly by up to
+    /// `percent` either way, so that entries written together, e.g. on warming up,
+    /// don't expire together. Entry given its own deadline with `put_until` isn't spread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percent` is greater than 100
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{LruCache, MockClock};
+    /// use std::time::Duration;
+    ///
+    /// let clock = MockClock::new();
+    /// let mut cache = LruCache::builder()
+    ///     .multiply_cap(64)
+    ///     .timeout_secs(100)
+    ///     .ttl_jitter_percent(10)
+    ///     .clock(clock.clone())
+    ///     .build();
+    ///
+    /// for key in 0..64 {
+    ///     cache.put(key, key);
+    /// }
+    ///
+    /// clock.advance(Duration::from_secs(100));
+    /// cache.evict();
+    /// assert!(0 < cache.len() && cache.len() < 64);
+    /// ```
+    pub fn ttl_jitter_percent(mut self, percent: usize) -> Self {
+        if percent > 100 {
+            panic!("TTL jitter defined with more than 100 percent")
+        }
+        self.jitter_percent = percent;
+        self
+    }
+
+    /// Seed random jitter of `ttl_jitter_percent`, along with refresh hint of
+    /// `get_with_refresh_hint`, so that entries expire and are refreshed at the same time
+    /// on every run, e.g. in tests. Randomly seeded by default.
+    pub fn jitter_seed(mut self, seed: u64) -> Self {
+        self.jitter_seed = Some(seed);
+        self
+    }
+
+    /// Hint to refresh entry `beta` times its compute time, given by `put_with_compute_time`,
+    /// before it expires on average, see `get_with_refresh_hint`. Greater `beta` refreshes
+    /// earlier, while zero never refreshes before expiry. 1 by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beta` is negative or not finite
+    pub fn refresh_beta(mut self, beta: f64) -> Self {
+        if !beta.is_finite() || beta < 0.0 {
+            panic!("Refresh beta defined with negative or non-finite value")
+        }
+        self.refresh_beta = beta;
+        self
+    }
+
+    /// Keep expired entry for `grace`, served as stale by `get_or_stale` meanwhile,
+    /// e.g. while it's reloaded in the background with `get_or_revalidate` of async cache,
+    /// before it's removed. Stale entry still counts toward capacity, and is evicted by
+    /// policy when full. No grace by default.
+    pub fn stale_while_revalidate(mut self, grace: Duration) -> Self {
+        self.grace = grace;
+        self
+    }
+
+    /// Measure expiry with given `clock` instead of `Instant::now`,
+    /// e.g. [`MockClock`](struct.MockClock.html) to test expiry without waiting.
+    /// Runtime daemon of async cache still wakes up on tokio's timer.
+    pub fn clock<C>(mut self, clock: C) -> Self
+    where
+        C: 'static + TimeSource + Send + Sync,
+    {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Use given eviction policy instead of its default
+    pub fn policy(mut self, policy: P) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Give weight of each entry to eviction policy, computed with `weigher`
+    /// on insertion and update. Without weigher, every entry has unit weight.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{policy::Weight, GdsfCache};
+    ///
+    /// let mut cache = GdsfCache::builder()
+    ///     .max_capacity(2)
+    ///     .timeout_secs(60)
+    ///     .weigher(|_: &usize, value: &String| Weight {
+    ///         cost: 1,
+    ///         size: value.len(),
+    ///     })
+    ///     .build();
+    ///
+    /// cache.put(1, String::from("a very long value"));
+    /// cache.put(2, String::from("short"));
+    ///
+    /// assert_eq!(cache.push(3, String::from("c")).map(|(key, _)| key), Some(1));
+    /// ```
+    pub fn weigher<W>(mut self, weigher: W) -> Self
+    where
+        W: 'static + Weigher<K, V> + Send + Sync,
+    {
+        self.weigher = Some(Box::new(weigher));
+        self
+    }
+
+    /// Hold entries whose total weight, given by weigher's size, is at most `max_weight`,
+    /// evicting victims chosen by policy until new entry fits. Entry heavier than
+    /// `max_weight` is rejected. Without weigher, every entry weighs 1.
+    ///
+    /// Use along with `multiply_cap` to bound the cache only by weight.
+    pub fn max_weight(mut self, max_weight: usize) -> Self {
+        self.max_weight = Some(max_weight);
+        self
+    }
+}
+
+impl<K: Hash + Eq, V, P: EvictionPolicy> Builder<K, V, P> {
+    /// Create new Cache
+    pub fn build(self) -> Cache<K, V, P> {
+        if self.cap == 0 {
+            panic!("Cache defined with 0 capacity")
+        }
+        let expiry = match (self.expiry, &self.custom_expiry) {
+            (Some(expiry), None) => expiry,
+            // every entry is given its own time-to-live by custom expiry
+            (_, Some(_)) | (None, None) => ExpiryMode::Never,
+        };
+        let seed = self.jitter_seed.unwrap_or_else(Random::seed);
+        let jitter = if self.jitter_percent > 0 {
+            Some(Jitter::new(self.jitter_percent, seed))
+        } else {
+            None
+        };
+        Cache {
+            storage: Storage::new(
+                self.cap,
+                self.grow,
+                self.max_weight,
+                self.policy,
+                expiry,
+                jitter,
+                self.clock.unwrap_or_else(|| Box::new(MonotonicClock)),
+            )
+            .with_grace(self.grace),
+            map: HashMap::with_capacity(self.cap),
+            weigher: self.weigher,
+            expiry: self.custom_expiry,
+            // apart from the jitter, not to draw the same numbers
+            refresh: EarlyRefresh::new(self.refresh_beta, !seed),
+        }
+    }
+}
+
+#[cfg(feature = "asynchronous")]
+impl<K, V, P> Builder<K, V, P>
+where
+    K: 'static + Hash + Eq + Sync + Send,
+    V: 'static + Clone + Send + Sync,
+    P: 'static + EvictionPolicy + Send + Sync,
+    P::Meta: Send + Sync,
+{
+    /// Create new async Cache, along with runtime daemon evicting expired entry
+    /// as soon as it expires, which is only spawned once any entry expires
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruAsyncCache;
+    /// use std::sync::Arc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let cache = LruAsyncCache::builder().max_capacity(2).build_async();
+    ///
+    ///     cache.put(1, "a").await;
+    ///     assert_eq!(cache.get(&1).await, Some("a"));
+    ///
+    ///     // no runtime daemon holds the cache
+    ///     assert_eq!(Arc::strong_count(&cache), 1);
+    /// }
+    /// ```
+    pub fn build_async(self) -> Arc<AsyncCache<K, V, P>> {
+        AsyncCache::wrap(self.build())
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Source of monotonic time the cache expires its entries with
+pub trait TimeSource {
+    /// Returns the current instant, never earlier than the one returned before
+    fn now(&self) -> Instant;
+}
+
+/// Time source reading `Instant::now`, used by default
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MonotonicClock;
+
+impl TimeSource for MonotonicClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Time source only moving forward when advanced by hand, so that expiry could be
+/// tested without waiting. Its clones share the same time.
+///
+/// # Example
+///
+/// ```
+/// use aba_cache as cache;
+/// use cache::{LruCache, MockClock};
+/// use std::time::Duration;
+///
+/// let clock = MockClock::new();
+/// let mut cache = LruCache::builder()
+///     .max_capacity(2)
+///     .timeout_secs(60)
+///     .clock(clock.clone())
+///     .build();
+///
+/// cache.put(1, "a");
+/// assert_eq!(cache.get(&1), Some(&"a"));
+///
+/// clock.advance(Duration::from_secs(60));
+/// assert_eq!(cache.get(&1), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    origin: Instant,
+    // nanoseconds since origin
+    elapsed: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    /// Create new clock, standing still until advanced
+    pub fn new() -> Self {
+        MockClock {
+            origin: Instant::now(),
+            elapsed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Move the clock, along with its clones, forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeSource for MockClock {
+    fn now(&self) -> Instant {
+        self.origin + Duration::from_nanos(self.elapsed.load(Ordering::SeqCst))
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::{Duration, Instant},
+};
+
+/// How entries of the cache expire, chosen on construction.
+///
+/// Time-to-live given on insertion, e.g. by `put_with_ttl`, replaces the duration
+/// counted since the entry is written, or since it's last accessed when the cache
+/// only expires after access.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ExpiryMode {
+    /// Expire entry after given duration since it's written, reading doesn't refresh it
+    ExpireAfterWrite(Duration),
+    /// Expire entry after given duration since it's last written or read
+    ExpireAfterAccess(Duration),
+    /// Expire entry on whichever comes first, `after_write` since it's written,
+    /// or `after_access` since it's last written or read
+    Both {
+        /// Duration since the entry is written
+        after_write: Duration,
+        /// Duration since the entry is last written or read
+        after_access: Duration,
+    },
+    /// Never expire entry, unless it's given its own time-to-live since it's written,
+    /// leaving the cache bounded only by its capacity
+    Never,
+}
+
+/// Value read from the cache, tagged with whether it's still fresh, or already expired
+/// and served within the grace window of `stale_while_revalidate`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Freshness<V> {
+    /// Value of entry not yet expired
+    Fresh(V),
+    /// Value of expired entry, to be refreshed
+    Stale(V),
+}
+
+impl<V> Freshness<V> {
+    /// Returns whether the value is stale
+    pub fn is_stale(&self) -> bool {
+        matches!(self, Freshness::Stale(_))
+    }
+
+    /// Returns the value, whether it's fresh or stale
+    pub fn into_inner(self) -> V {
+        match self {
+            Freshness::Fresh(value) | Freshness::Stale(value) => value,
+        }
+    }
+
+    /// Maps the value with `f`, keeping its freshness
+    pub fn map<U, F: FnOnce(V) -> U>(self, f: F) -> Freshness<U> {
+        match self {
+            Freshness::Fresh(value) => Freshness::Fresh(f(value)),
+            Freshness::Stale(value) => Freshness::Stale(f(value)),
+        }
+    }
+}
+
+/// Pseudorandom numbers of splitmix64, cheap and reproducible from given seed.
+pub(super) struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub(super) fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    /// Returns a seed differing on every call, for randomness not seeded by user.
+    pub(super) fn seed() -> u64 {
+        RandomState::new().build_hasher().finish()
+    }
+
+    pub(super) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number uniformly distributed in `(0, 1]`.
+    pub(super) fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Spreads durations of entries by up to given percent either way, so that
+/// entries written together don't expire together.
+pub(super) struct Jitter {
+    percent: u64,
+    random: Random,
+}
+
+impl Jitter {
+    pub(super) fn new(percent: usize, seed: u64) -> Self {
+        Jitter {
+            percent: percent as u64,
+            random: Random::new(seed),
+        }
+    }
+
+    /// Returns given duration in milliseconds, moved randomly within the jitter.
+    pub(super) fn spread(&mut self, millis: u64) -> u64 {
+        let span = u128::from(millis) * u128::from(self.percent) / 100;
+        if span == 0 || millis == u64::MAX {
+            return millis;
+        }
+        let offset = u128::from(self.random.next_u64()) % (2 * span + 1);
+        (u128::from(millis) - span + offset).min(u128::from(u64::MAX)) as u64
+    }
+}
+
+/// Tells whether an entry should be refreshed before it expires, with rising
+/// probability as it nears expiry, following XFetch of "Optimal Probabilistic
+/// Cache Stampede Prevention". Entry taking longer to compute is refreshed earlier,
+/// by `beta` times its compute time on average.
+pub(super) struct EarlyRefresh {
+    beta: f64,
+    random: Random,
+}
+
+impl EarlyRefresh {
+    pub(super) fn new(beta: f64, seed: u64) -> Self {
+        EarlyRefresh {
+            beta,
+            random: Random::new(seed),
+        }
+    }
+
+    /// Returns whether entry expiring after `remaining`, whose value took `compute_time`
+    /// to compute, should be refreshed now.
+    pub(super) fn due(&mut self, remaining: Duration, compute_time: Duration) -> bool {
+        if compute_time == Duration::from_secs(0) || self.beta == 0.0 {
+            return false;
+        }
+        let gap = compute_time.as_secs_f64() * self.beta * -self.random.next_f64().ln();
+        gap >= remaining.as_secs_f64()
+    }
+}
+
+/// How long an entry lives since it's written.
+#[derive(Debug, Copy, Clone)]
+pub(super) enum Lifetime {
+    /// According to expiry mode
+    Default,
+    /// Replacing the duration of expiry mode, see `ExpiryMode::lifetimes`
+    Ttl(Duration),
+    /// Until given instant, regardless of expiry mode
+    Until(Instant),
+}
+
+impl Lifetime {
+    /// Returns the time-to-live replacing the duration of expiry mode, if any.
+    pub(super) fn ttl(self) -> Option<Duration> {
+        match self {
+            Lifetime::Ttl(ttl) => Some(ttl),
+            Lifetime::Default | Lifetime::Until(_) => None,
+        }
+    }
+}
+
+impl ExpiryMode {
+    /// Returns the mode expiring entry after given `timeout` since it's last
+    /// written or read, or never for zero `timeout`.
+    pub(super) fn timeout(timeout: Duration) -> Self {
+        if timeout == Duration::from_secs(0) {
+            ExpiryMode::Never
+        } else {
+            ExpiryMode::ExpireAfterAccess(timeout)
+        }
+    }
+
+    /// Returns the duration since the entry is written, and the duration since
+    /// it's last accessed, either replaced with given `ttl`.
+    pub(super) fn lifetimes(&self, ttl: Option<Duration>) -> (Option<Duration>, Option<Duration>) {
+        match *self {
+            ExpiryMode::ExpireAfterWrite(after_write) => (Some(ttl.unwrap_or(after_write)), None),
+            ExpiryMode::ExpireAfterAccess(after_access) => {
+                (None, Some(ttl.unwrap_or(after_access)))
+            }
+            ExpiryMode::Both {
+                after_write,
+                after_access,
+            } => (Some(ttl.unwrap_or(after_write)), Some(after_access)),
+            ExpiryMode::Never => (ttl, None),
+        }
+    }
+
+    /// Returns whether entry expires after it's last read, so that reading it
+    /// has to refresh its timestamp.
+    pub(super) fn tracks_access(&self) -> bool {
+        match *self {
+            ExpiryMode::ExpireAfterAccess(_) | ExpiryMode::Both { .. } => true,
+            ExpiryMode::ExpireAfterWrite(_) | ExpiryMode::Never => false,
+        }
+    }
+}
+
+/// Compute how long each entry lives, on its creation, update and read,
+/// so that it could depend on the entry itself, e.g. HTTP max-age of the value.
+///
+/// # Example
+///
+/// ```
+/// use aba_cache as cache;
+/// use cache::{Expiry, LruCache};
+/// use std::time::Duration;
+///
+/// /// Value carries its own max-age in seconds
+/// struct MaxAge;
+///
+/// impl Expiry<&str, (u64, &str)> for MaxAge {
+///     fn expire_after_create(&self, _: &&str, value: &(u64, &str)) -> Duration {
+///         Duration::from_secs(value.0)
+///     }
+/// }
+///
+/// let mut cache = LruCache::builder()
+///     .max_capacity(2)
+///     .expiry(MaxAge)
+///     .build();
+///
+/// cache.put("index", (0, "<html>"));
+/// cache.put("logo", (3600, "<svg>"));
+///
+/// assert_eq!(cache.get(&"index"), None);
+/// assert_eq!(cache.get(&"logo"), Some(&(3600, "<svg>")));
+/// ```
+pub trait Expiry<K, V> {
+    /// Returns the duration the entry lives after it's created
+    fn expire_after_create(&self, key: &K, value: &V) -> Duration;
+
+    /// Returns the duration the entry lives after its value is replaced, `remaining`
+    /// is the duration left before it expires. Treated as creation by default.
+    fn expire_after_update(&self, key: &K, value: &V, remaining: Duration) -> Duration {
+        let _ = remaining;
+        self.expire_after_create(key, value)
+    }
+
+    /// Returns the duration the entry lives after it's read, `remaining` is the
+    /// duration left before it expires. Keeps `remaining` by default. Entry put
+    /// with `put_until` never lives past its deadline, however long it's given.
+    fn expire_after_read(&self, key: &K, value: &V, remaining: Duration) -> Duration {
+        let _ = (key, value);
+        remaining
+    }
+}
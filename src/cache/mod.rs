@@ -0,0 +1,817 @@
+use crate::policy::{EvictionPolicy, Lru, Weigher, Weight};
+#[cfg(not(feature = "asynchronous"))]
+use std::rc::Rc;
+#[cfg(feature = "asynchronous")]
+use std::sync::Arc;
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display},
+    hash::{BuildHasher, Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+use expiry::{EarlyRefresh, Jitter, Lifetime, Random};
+use storage::{Pointer, Storage};
+
+pub use builder::Builder;
+pub use clock::{MockClock, MonotonicClock, TimeSource};
+pub use expiry::{Expiry, ExpiryMode, Freshness};
+
+#[cfg(feature = "asynchronous")]
+pub(crate) mod asynchronous;
+mod builder;
+mod clock;
+mod expiry;
+mod storage;
+mod timer_wheel;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(not(feature = "asynchronous"))]
+type Ref<T> = Rc<T>;
+
+#[cfg(feature = "asynchronous")]
+type Ref<T> = Arc<T>;
+
+type BoxWeigher<K, V> = Box<dyn Weigher<K, V> + Send + Sync>;
+
+type BoxExpiry<K, V> = Box<dyn Expiry<K, V> + Send + Sync>;
+
+type BoxClock = Box<dyn TimeSource + Send + Sync>;
+
+/// Error returned when the entry is heavier than `max_weight` of the cache,
+/// giving back the rejected key-value pair
+#[derive(Debug, PartialEq)]
+pub struct Overweight<K, V> {
+    /// Key of the rejected entry
+    pub key: K,
+    /// Value of the rejected entry
+    pub value: V,
+}
+
+impl<K, V> Display for Overweight<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("entry is heavier than max weight of the cache")
+    }
+}
+
+impl<K: Debug, V: Debug> Error for Overweight<K, V> {}
+
+/// Cache with pluggable eviction strategy, LRU by default
+pub struct Cache<K, V, P: EvictionPolicy = Lru> {
+    storage: Storage<Ref<K>, V, P>,
+    map: HashMap<Ref<K>, Pointer>,
+    weigher: Option<BoxWeigher<K, V>>,
+    expiry: Option<BoxExpiry<K, V>>,
+    refresh: EarlyRefresh,
+}
+
+impl<K: Hash + Eq, V, P: EvictionPolicy + Default> Cache<K, V, P> {
+    /// Create new Cache, which will expiring its entry after `timeout_secs`
+    /// and allocating new slab with capacity `multiply_cap` when no space
+    /// is ready and no entry expires. Entry never expires for zero `timeout_secs`.
+    pub fn new(multiply_cap: usize, timeout_secs: u64) -> Self {
+        Self::with_timeout(multiply_cap, Duration::from_secs(timeout_secs))
+    }
+
+    /// Create new Cache, the same as `new`, but expiring its entry after `timeout`,
+    /// counted in milliseconds
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{LruCache, MockClock};
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = LruCache::with_timeout(2, Duration::from_millis(100));
+    ///
+    /// cache.put(1, "a");
+    /// assert_eq!(cache.get(&1), Some(&"a"));
+    ///
+    /// // the same cache, built with mock clock to move forward by hand
+    /// let clock = MockClock::new();
+    /// let mut cache = LruCache::builder()
+    ///     .multiply_cap(2)
+    ///     .timeout(Duration::from_millis(100))
+    ///     .clock(clock.clone())
+    ///     .build();
+    ///
+    /// cache.put(1, "a");
+    /// clock.advance(Duration::from_millis(100));
+    /// assert_eq!(cache.get(&1), None);
+    /// ```
+    pub fn with_timeout(multiply_cap: usize, timeout: Duration) -> Self {
+        if multiply_cap == 0 {
+            panic!("Cache defined with 0 capacity")
+        }
+        Cache {
+            storage: Storage::new(
+                multiply_cap,
+                true,
+                None,
+                P::default(),
+                ExpiryMode::timeout(timeout),
+                None,
+                Box::new(MonotonicClock),
+            ),
+            map: HashMap::with_capacity(multiply_cap),
+            weigher: None,
+            expiry: None,
+            refresh: EarlyRefresh::new(1.0, Random::seed()),
+        }
+    }
+
+    /// Returns a [`Builder`](struct.Builder.html) to configure new Cache,
+    /// e.g. to create Cache which holds at most given number of entries
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruCache;
+    ///
+    /// let mut cache = LruCache::builder()
+    ///     .max_capacity(2)
+    ///     .timeout_secs(60)
+    ///     .build();
+    ///
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.put(3, "c");
+    ///
+    /// assert_eq!(cache.len(), 2);
+    /// assert_eq!(cache.get(&1), None);
+    /// ```
+    pub fn builder() -> Builder<K, V, P> {
+        Builder::new(P::default())
+    }
+}
+
+impl<K: Hash + Eq, V, P: EvictionPolicy> Cache<K, V, P> {
+    /// Returns a reference to the value of the key in the cache or `None` if it is not
+    /// present in the cache. Records the access to eviction policy if it exists, e.g.
+    /// moves the key to the head of the LRU list. When neither the policy, the expiry mode,
+    /// nor the [`Expiry`](trait.Expiry.html) of the cache track access, this is the same
+    /// as `peek`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2, 60);
+    ///
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.put(2, "c");
+    /// cache.put(3, "d");
+    ///
+    /// assert_eq!(cache.get(&1), Some(&"a"));
+    /// assert_eq!(cache.get(&2), Some(&"c"));
+    /// assert_eq!(cache.get(&3), Some(&"d"));
+    /// ```
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        Ref<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.reads_shared() {
+            self.read(key)
+        } else if let Some(Freshness::Fresh(index)) = self.lookup(key) {
+            Some(self.storage.pair(index).1)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the value of the key in the cache, the same as `get`,
+    /// tagged as [`Stale`](enum.Freshness.html) once it's expired, but still within
+    /// the grace window of `stale_while_revalidate`. Stale entry is left untouched,
+    /// until it's put again or removed after the grace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{Freshness, LruCache, MockClock};
+    /// use std::time::Duration;
+    ///
+    /// let clock = MockClock::new();
+    /// let mut cache = LruCache::builder()
+    ///     .max_capacity(2)
+    ///     .timeout_secs(60)
+    ///     .stale_while_revalidate(Duration::from_secs(30))
+    ///     .clock(clock.clone())
+    ///     .build();
+    ///
+    /// cache.put("rate", 1.5);
+    /// assert_eq!(cache.get_or_stale(&"rate"), Some(Freshness::Fresh(&1.5)));
+    ///
+    /// clock.advance(Duration::from_secs(60));
+    /// assert_eq!(cache.get(&"rate"), None);
+    /// assert_eq!(cache.get_or_stale(&"rate"), Some(Freshness::Stale(&1.5)));
+    ///
+    /// clock.advance(Duration::from_secs(30));
+    /// assert_eq!(cache.get_or_stale(&"rate"), None);
+    /// ```
+    pub fn get_or_stale<Q>(&mut self, key: &Q) -> Option<Freshness<&V>>
+    where
+        Ref<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.reads_shared() {
+            let index = *self.map.get(key)?;
+            let storage = &self.storage;
+            storage
+                .read(index)
+                .map(Freshness::Fresh)
+                .or_else(move || storage.stale(index).map(Freshness::Stale))
+        } else {
+            let found = self.lookup(key)?;
+            let storage = &self.storage;
+            Some(found.map(move |index| storage.pair(index).1))
+        }
+    }
+
+    /// Look up the entry of the key, recording the access of fresh entry,
+    /// and removing the entry expired beyond the grace.
+    fn lookup<Q>(&mut self, key: &Q) -> Option<Freshness<Pointer>>
+    where
+        Ref<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.map.is_empty() {
+            return None;
+        }
+        let index = *self.map.get(key)?;
+        match self.storage.get(index) {
+            None => {
+                self.map.remove(key);
+                return None;
+            }
+            Some(Freshness::Stale(_)) => return Some(Freshness::Stale(index)),
+            Some(Freshness::Fresh(_)) => {}
+        }
+        if let Some(expiry) = &self.expiry {
+            let (key, value) = self.storage.pair(index);
+            let duration = expiry.expire_after_read(key, value, self.storage.remaining(index));
+            self.storage.expire_after(index, duration);
+        }
+        Some(Freshness::Fresh(index))
+    }
+
+    /// Mark the stale entry of the key as being reloaded, returns whether it's
+    /// not already, so that only one reload runs until it's put again.
+    #[cfg(feature = "asynchronous")]
+    pub(crate) fn revalidate<Q>(&mut self, key: &Q) -> bool
+    where
+        Ref<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.map.get(key) {
+            Some(&index) => self.storage.revalidate(index),
+            None => false,
+        }
+    }
+
+    /// Returns a reference to the value of the key in the cache, the same as `get`, along with
+    /// whether it should be refreshed now, before it expires. The hint is given with rising
+    /// probability as the entry nears expiry, sooner for entry taking longer to compute,
+    /// as recorded by `put_with_compute_time`, so that only a few readers refresh it ahead of
+    /// the others, instead of all of them at once on its expiry. Entry put without its
+    /// compute time is never hinted, nor is entry which only expires after it's last read,
+    /// as reading keeps it alive. See `refresh_beta` of [`Builder`](struct.Builder.html).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{ExpiryMode, LruCache, MockClock};
+    /// use std::time::Duration;
+    ///
+    /// let clock = MockClock::new();
+    /// let mut cache = LruCache::builder()
+    ///     .max_capacity(2)
+    ///     .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(60)))
+    ///     .jitter_seed(7)
+    ///     .clock(clock.clone())
+    ///     .build();
+    ///
+    /// cache.put_with_compute_time("report", "slow", Duration::from_secs(5));
+    /// assert_eq!(cache.get_with_refresh_hint(&"report"), Some((&"slow", false)));
+    ///
+    /// // refreshed within a few reads as it's about to expire
+    /// clock.advance(Duration::from_millis(59_999));
+    /// let refreshed = (0..10).any(|_| cache.get_with_refresh_hint(&"report").unwrap().1);
+    /// assert!(refreshed);
+    /// ```
+    pub fn get_with_refresh_hint<Q>(&mut self, key: &Q) -> Option<(&V, bool)>
+    where
+        Ref<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = *self.map.get(key)?;
+        // deadline of the write, before reading pushes back the idle one
+        let remaining = self.storage.write_remaining(index);
+        self.get(key)?;
+        let compute_time = self.storage.compute_time(index);
+        let refresh =
+            remaining.map_or(false, |remaining| self.refresh.due(remaining, compute_time));
+        Some((self.storage.pair(index).1, refresh))
+    }
+
+    /// Returns a reference to the value of the key in the cache or `None` if it is not
+    /// present in the cache or already expired, without recording the access. Expired entry
+    /// is left to be removed by `evict` or reused by `put`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruCache;
+    ///
+    /// let mut cache = LruCache::builder()
+    ///     .max_capacity(2)
+    ///     .timeout_secs(60)
+    ///     .build();
+    ///
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    ///
+    /// assert_eq!(cache.peek(&1), Some(&"a"));
+    /// assert_eq!(cache.push(3, "c"), Some((1, "a")));
+    /// ```
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Ref<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if let Some(&index) = self.map.get(key) {
+            self.storage.peek(index)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the current instant of the clock of the cache.
+    #[cfg(feature = "asynchronous")]
+    pub(crate) fn now(&self) -> Instant {
+        self.storage.instant()
+    }
+
+    /// Returns the earliest time an expired entry could be removed by `evict`,
+    /// if any entry expires.
+    #[cfg(feature = "asynchronous")]
+    pub(crate) fn next_expiry(&self) -> Option<Instant> {
+        self.storage.next_expiry()
+    }
+
+    /// Returns whether reading the value of the key only needs shared access,
+    /// as neither the policy, the expiry mode, nor the expiry of the cache track access.
+    pub(crate) fn reads_shared(&self) -> bool {
+        !P::TRACK_ACCESS && self.expiry.is_none() && !self.storage.tracks_access()
+    }
+
+    /// Read the value of the key with only shared access, for policy not tracking access.
+    pub(crate) fn read<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Ref<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if let Some(&index) = self.map.get(key) {
+            self.storage.read(index)
+        } else {
+            None
+        }
+    }
+
+    /// Puts a key-value pair into cache. If the key already exists in the cache, then it updates
+    /// the key's value and returns the old value. Otherwise, `None` is returned, even when
+    /// another entry is removed to make room, see `push`.
+    ///
+    /// # Overweight entry
+    ///
+    /// Entry heavier than `max_weight` is dropped, returning `None` as if it's put,
+    /// and leaving the cache untouched, so that the old value of the key, if any,
+    /// is kept. Use `try_push` to tell it apart, getting the entry back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2, 60);
+    ///
+    /// assert_eq!(None, cache.put(1, "a"));
+    /// assert_eq!(None, cache.put(2, "b"));
+    /// assert_eq!(Some("b"), cache.put(2, "beta"));
+    ///
+    /// assert_eq!(cache.get(&1), Some(&"a"));
+    /// assert_eq!(cache.get(&2), Some(&"beta"));
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        old_value(self.insert(key, value, Lifetime::Default, Duration::from_secs(0)))
+    }
+
+    /// Puts a key-value pair into cache. If the key already exists in the cache, then it updates
+    /// the key's value and returns the given key with old value. If an entry is removed to
+    /// accomodate new entry, either because it expires or the cache is full, then it returns
+    /// the removed key-value pair. Otherwise, `None` is returned. Entry heavier than `max_weight`
+    /// is given back, see `try_push`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruCache;
+    ///
+    /// let mut cache = LruCache::builder()
+    ///     .max_capacity(2)
+    ///     .timeout_secs(60)
+    ///     .build();
+    ///
+    /// assert_eq!(None, cache.push(1, "a"));
+    /// assert_eq!(None, cache.push(2, "b"));
+    /// assert_eq!(Some((2, "b")), cache.push(2, "beta"));
+    /// assert_eq!(Some((1, "a")), cache.push(3, "c"));
+    ///
+    /// assert_eq!(cache.get(&2), Some(&"beta"));
+    /// assert_eq!(cache.get(&3), Some(&"c"));
+    /// ```
+    pub fn push(&mut self, key: K, value: V) -> Option<(K, V)> {
+        match self.try_push(key, value) {
+            Ok(removed) => removed.into_iter().next(),
+            Err(Overweight { key, value }) => Some((key, value)),
+        }
+    }
+
+    /// Puts a key-value pair into cache, the same as `push`, but returns every removed
+    /// key-value pair, starting with the given key with old value if it already exists.
+    /// More than one entry could be removed to make room for new entry when the cache is
+    /// built with `max_weight`. Entry heavier than `max_weight` is rejected, leaving the
+    /// cache untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{policy::Weight, LruCache, Overweight};
+    ///
+    /// let mut cache = LruCache::builder()
+    ///     .multiply_cap(4)
+    ///     .max_weight(10)
+    ///     .weigher(|_: &usize, value: &&str| Weight {
+    ///         cost: 1,
+    ///         size: value.len(),
+    ///     })
+    ///     .timeout_secs(60)
+    ///     .build();
+    ///
+    /// assert_eq!(cache.try_push(1, "aaaa"), Ok(vec![]));
+    /// assert_eq!(cache.try_push(2, "bbbb"), Ok(vec![]));
+    /// assert_eq!(cache.try_push(3, "cccccccc"), Ok(vec![(1, "aaaa"), (2, "bbbb")]));
+    /// assert_eq!(
+    ///     cache.try_push(4, "dddddddddddd"),
+    ///     Err(Overweight {
+    ///         key: 4,
+    ///         value: "dddddddddddd"
+    ///     })
+    /// );
+    /// assert_eq!(cache.weight(), 8);
+    /// ```
+    pub fn try_push(&mut self, key: K, value: V) -> Result<Vec<(K, V)>, Overweight<K, V>> {
+        self.insert(key, value, Lifetime::Default, Duration::from_secs(0))
+            .map(Removed::into_vec)
+    }
+
+    /// Puts a key-value pair into cache, the same as `put`, but the entry expires after
+    /// given `ttl` instead of the timeout of the cache, see [`ExpiryMode`](enum.ExpiryMode.html),
+    /// or the duration given by its [`Expiry`](trait.Expiry.html).
+    /// Reading the entry refreshes it with the same `ttl` when the cache expires entry after
+    /// access, while putting it again without `ttl` restores the timeout of the cache.
+    ///
+    /// # Overweight entry
+    ///
+    /// Entry heavier than `max_weight` is dropped, returning `None`,
+    /// and keeping the old value of the key, if any, the same as `put`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{LruCache, MockClock};
+    /// use std::time::Duration;
+    ///
+    /// let clock = MockClock::new();
+    /// let mut cache = LruCache::builder()
+    ///     .multiply_cap(2)
+    ///     .timeout_secs(60)
+    ///     .clock(clock.clone())
+    ///     .build();
+    ///
+    /// cache.put_with_ttl(1, "a", Duration::from_secs(1));
+    /// cache.put(2, "b");
+    ///
+    /// clock.advance(Duration::from_secs(1));
+    /// cache.evict();
+    ///
+    /// assert_eq!(cache.get(&1), None);
+    /// assert_eq!(cache.get(&2), Some(&"b"));
+    /// ```
+    pub fn put_with_ttl(&mut self, key: K, value: V, ttl: Duration) -> Option<V> {
+        old_value(self.insert(key, value, Lifetime::Ttl(ttl), Duration::from_secs(0)))
+    }
+
+    /// Puts a key-value pair into cache, the same as `put`, but the entry expires at given
+    /// `deadline`, measured by the clock of the cache, regardless of its timeout. Neither
+    /// reading nor the expiry mode of the cache extend it, while putting it again without
+    /// `deadline` restores the timeout of the cache.
+    ///
+    /// # Overweight entry
+    ///
+    /// Entry heavier than `max_weight` is dropped, returning `None`,
+    /// and keeping the old value of the key, if any, the same as `put`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{LruCache, MockClock, TimeSource};
+    /// use std::time::Duration;
+    ///
+    /// let clock = MockClock::new();
+    /// let mut cache = LruCache::builder()
+    ///     .max_capacity(2)
+    ///     .timeout_secs(60)
+    ///     .clock(clock.clone())
+    ///     .build();
+    ///
+    /// let expires_at = clock.now() + Duration::from_secs(90);
+    /// cache.put_until("token", "secret", expires_at);
+    ///
+    /// clock.advance(Duration::from_secs(50));
+    /// assert_eq!(cache.get(&"token"), Some(&"secret"));
+    ///
+    /// clock.advance(Duration::from_secs(40));
+    /// assert_eq!(cache.get(&"token"), None);
+    /// ```
+    pub fn put_until(&mut self, key: K, value: V, deadline: Instant) -> Option<V> {
+        old_value(self.insert(
+            key,
+            value,
+            Lifetime::Until(deadline),
+            Duration::from_secs(0),
+        ))
+    }
+
+    /// Puts a key-value pair into cache, the same as `put`, along with the `compute_time`
+    /// it took to compute the value, so that `get_with_refresh_hint` hints to refresh it
+    /// earlier the longer it takes. Not to be confused with `cost` of
+    /// [`Weight`](policy/struct.Weight.html), which is weighed by GDSF policy.
+    ///
+    /// # Overweight entry
+    ///
+    /// Entry heavier than `max_weight` is dropped, returning `None`,
+    /// and keeping the old value of the key, if any, the same as `put`.
+    pub fn put_with_compute_time(&mut self, key: K, value: V, compute_time: Duration) -> Option<V> {
+        old_value(self.insert(key, value, Lifetime::Default, compute_time))
+    }
+
+    /// Insert the entry living for given `lifetime`, the duration given by expiry,
+    /// or the timeout of the cache, whose value took `compute_time` to compute,
+    /// returns every removed key-value pair.
+    fn insert(
+        &mut self,
+        key: K,
+        value: V,
+        mut lifetime: Lifetime,
+        compute_time: Duration,
+    ) -> Result<Removed<K, V>, Overweight<K, V>> {
+        let weight = self.weigh(&key, &value);
+        if !self.storage.admits(weight.size) {
+            return Err(Overweight { key, value });
+        }
+        let mut removed = Removed {
+            replaced: None,
+            evicted: Vec::new(),
+        };
+        if let Some(&index) = self.map.get(&key) {
+            if let (Lifetime::Default, Some(expiry)) = (lifetime, &self.expiry) {
+                let remaining = self.storage.remaining(index);
+                lifetime = Lifetime::Ttl(expiry.expire_after_update(&key, &value, remaining));
+            }
+            if self.storage.fits(index, weight.size) {
+                removed.replaced = Some((key, self.storage.update(index, value, weight, lifetime)));
+                self.storage.set_compute_time(index, compute_time);
+                return Ok(removed);
+            }
+            // make room for heavier value as if it's new entry
+            self.map.remove(&key);
+            let (old_key, old_data) = self.storage.remove(index);
+            removed.replaced = Some((unwrap_key(old_key), old_data));
+        } else if let (Lifetime::Default, Some(expiry)) = (lifetime, &self.expiry) {
+            lifetime = Lifetime::Ttl(expiry.expire_after_create(&key, &value));
+        }
+        let hash = {
+            let mut hasher = self.map.hasher().build_hasher();
+            key.hash(&mut hasher);
+            hasher.finish()
+        };
+        for (old_key, old_data) in self.storage.reserve(weight.size, hash) {
+            self.map.remove(&old_key);
+            removed.evicted.push((unwrap_key(old_key), old_data));
+        }
+        let key = Ref::new(key);
+        let (idx, old_pair) = self.storage.put(key.clone(), value, hash, weight, lifetime);
+        if let Some((old_key, old_data)) = old_pair {
+            self.map.remove(&old_key);
+            removed.evicted.push((unwrap_key(old_key), old_data));
+        }
+        self.storage.set_compute_time(idx, compute_time);
+        self.map.insert(key, idx);
+        Ok(removed)
+    }
+
+    /// Removes expired entry, once it's beyond the grace of `stale_while_revalidate`.
+    /// This operation will deallocate empty slab caused by entry removal if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{LruCache, MockClock};
+    /// use std::time::Duration;
+    ///
+    /// let clock = MockClock::new();
+    /// let mut cache = LruCache::builder()
+    ///     .multiply_cap(2)
+    ///     .timeout_secs(1)
+    ///     .clock(clock.clone())
+    ///     .build();
+    ///
+    /// cache.put(String::from("1"), "one");
+    /// cache.put(String::from("2"), "two");
+    /// cache.put(String::from("3"), "three");
+    ///
+    /// assert_eq!(cache.len(), 3);
+    /// assert_eq!(cache.capacity(), 4);
+    ///
+    /// clock.advance(Duration::from_secs(1));
+    /// cache.evict();
+    ///
+    /// assert_eq!(cache.len(), 0);
+    /// assert_eq!(cache.capacity(), 0);
+    /// ```
+    pub fn evict(&mut self) {
+        if !self.is_empty() {
+            self.storage.evict().drain(..).for_each(|key| {
+                self.map.remove(&key);
+            })
+        }
+    }
+
+    /// Returns the weight of the entry given by the weigher, or unit weight without one.
+    fn weigh(&self, key: &K, value: &V) -> Weight {
+        self.weigher
+            .as_ref()
+            .map_or_else(Weight::default, |weigher| weigher.weigh(key, value))
+    }
+
+    /// Returns the eviction policy of the cache.
+    #[cfg(test)]
+    pub(crate) fn policy(&self) -> &P {
+        self.storage.policy()
+    }
+
+    /// Returns the maximum number of key-value pairs the cache can hold.
+    /// Note that on data insertion, when no space is available and no
+    /// entry is timeout, then capacity will be added with `multiply_cap`
+    /// to accomodate, unless the cache is built with `max_capacity`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruCache;
+    ///
+    /// let mut cache: LruCache<usize, &str> = LruCache::new(2, 60);
+    /// assert_eq!(cache.capacity(), 2);
+    ///
+    /// cache.put(1, "a");
+    /// assert_eq!(cache.capacity(), 2);
+    ///
+    /// cache.put(2, "b");
+    /// assert_eq!(cache.capacity(), 2);
+    ///
+    /// cache.put(3, "c");
+    /// assert_eq!(cache.capacity(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+
+    /// Returns the total weight of entries in the cache, given by weigher's size,
+    /// or the number of entries without weigher.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::{policy::Weight, LruCache};
+    ///
+    /// let mut cache = LruCache::builder()
+    ///     .multiply_cap(2)
+    ///     .weigher(|_: &usize, value: &String| Weight {
+    ///         cost: 1,
+    ///         size: value.len(),
+    ///     })
+    ///     .timeout_secs(60)
+    ///     .build();
+    ///
+    /// cache.put(1, String::from("a"));
+    /// cache.put(2, String::from("bcd"));
+    /// assert_eq!(cache.weight(), 4);
+    /// ```
+    pub fn weight(&self) -> usize {
+        self.storage.weight()
+    }
+
+    /// Returns the number of key-value pairs that are currently in the the cache.
+    /// Note that len should be less than or equal to capacity
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2, 60);
+    /// assert_eq!(cache.len(), 0);
+    ///
+    /// cache.put(1, "a");
+    /// assert_eq!(cache.len(), 1);
+    ///
+    /// cache.put(2, "b");
+    /// assert_eq!(cache.len(), 2);
+    /// assert_eq!(cache.capacity(), 2);
+    ///
+    /// cache.put(3, "c");
+    /// assert_eq!(cache.len(), 3);
+    /// assert_eq!(cache.capacity(), 4);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns a bool indicating whether the cache is empty or not.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aba_cache as cache;
+    /// use cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2, 60);
+    /// assert!(cache.is_empty());
+    ///
+    /// cache.put(1, "a");
+    /// assert!(!cache.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+/// Key-value pairs removed on insertion.
+struct Removed<K, V> {
+    // the given key with its old value, if it already exists
+    replaced: Option<(K, V)>,
+    // expired entries and victims chosen by policy, making room
+    evicted: Vec<(K, V)>,
+}
+
+impl<K, V> Removed<K, V> {
+    /// Returns every removed key-value pair, starting with the replaced one.
+    fn into_vec(self) -> Vec<(K, V)> {
+        self.replaced.into_iter().chain(self.evicted).collect()
+    }
+}
+
+/// Returns the old value of the key, if the insertion replaced it.
+fn old_value<K, V>(inserted: Result<Removed<K, V>, Overweight<K, V>>) -> Option<V> {
+    inserted.ok()?.replaced.map(|(_, old_data)| old_data)
+}
+
+/// Take back the key from its reference, once it's no longer shared by the map.
+fn unwrap_key<K>(key: Ref<K>) -> K {
+    match Ref::try_unwrap(key) {
+        Ok(key) => key,
+        Err(_) => unreachable!("removed key is still referenced"),
+    }
+}
@@ -0,0 +1,591 @@
+use super::{
+    timer_wheel::{TimerNode, TimerWheel},
+    BoxClock, ExpiryMode, Freshness, Jitter, Lifetime,
+};
+use crate::policy::{EvictionPolicy, Handle, Weight};
+use slab::Slab;
+use std::{
+    convert::TryFrom,
+    mem,
+    ops::{Index, IndexMut},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(super) enum Pointer {
+    Null,
+    InternalPointer { slab: usize, pos: usize },
+}
+
+impl Pointer {
+    #[inline]
+    pub(super) fn null() -> Pointer {
+        Pointer::Null
+    }
+
+    // Returns `true` if this pointer is null.
+    #[inline]
+    pub(super) fn is_null(&self) -> bool {
+        *self == Pointer::Null
+    }
+
+    // Returns the handle exposed to eviction policy.
+    #[inline]
+    fn handle(self) -> Handle {
+        if let Pointer::InternalPointer { slab, pos } = self {
+            Handle { slab, pos }
+        } else {
+            panic!("handle of null pointer");
+        }
+    }
+}
+
+impl From<Handle> for Pointer {
+    #[inline]
+    fn from(handle: Handle) -> Self {
+        Pointer::InternalPointer {
+            slab: handle.slab,
+            pos: handle.pos,
+        }
+    }
+}
+
+/// Entries are scheduled on a timer wheel by the time they expire, as each
+/// entry could have its own time-to-live, delayed by the grace they're still
+/// served as stale. The order used for eviction is maintained by the policy.
+pub(super) struct Storage<K, V, P: EvictionPolicy> {
+    slabs: Slab<Slab<Entry<K, V, P::Meta>>>,
+    policy: P,
+
+    cap: usize,
+    len: usize,
+
+    timers: TimerWheel,
+    // monotonic, unaffected by changes of wall-clock time
+    clock: BoxClock,
+    origin: Instant,
+
+    grow: bool,
+    expiry: ExpiryMode,
+    jitter: Option<Jitter>,
+    // milliseconds expired entry is kept, served as stale
+    grace: u64,
+
+    weight: usize,
+    max_weight: Option<usize>,
+}
+
+pub(super) struct Entry<K, V, M> {
+    key: K,
+    // last written or read
+    timestamp: u64,
+    write_expire_at: u64,
+    idle_millis: Option<u64>,
+    // deadline given on write, never extended by read
+    deadline: u64,
+    // time taken to compute the data
+    compute_millis: u64,
+    // expired, and being reloaded
+    revalidating: bool,
+    data: V,
+    weight: usize,
+
+    timer: TimerNode,
+
+    meta: M,
+}
+
+impl<K, V, M: Default> Entry<K, V, M> {
+    fn new(key: K, data: V, weight: usize) -> Self {
+        Entry {
+            key,
+            timestamp: 0,
+            write_expire_at: 0,
+            idle_millis: None,
+            deadline: u64::MAX,
+            compute_millis: 0,
+            revalidating: false,
+            data,
+            weight,
+            timer: TimerNode::default(),
+            meta: M::default(),
+        }
+    }
+}
+
+impl<K, V, M> Entry<K, V, M> {
+    /// Returns the time the entry expires, in milliseconds since storage is created.
+    #[inline]
+    fn expire_at(&self) -> u64 {
+        match self.idle_millis {
+            Some(idle_millis) => self
+                .write_expire_at
+                .min(self.timestamp.saturating_add(idle_millis)),
+            None => self.write_expire_at,
+        }
+    }
+}
+
+/// Returns the number of whole milliseconds covering given duration.
+#[inline]
+fn ceil_millis(duration: Duration) -> u64 {
+    let millis = duration.as_millis() + u128::from(duration.subsec_nanos() % 1_000_000 != 0);
+    u64::try_from(millis).unwrap_or(u64::MAX)
+}
+
+/// Policy's view of the entries.
+struct Metas<'a, K, V, M>(&'a mut Slab<Slab<Entry<K, V, M>>>);
+
+impl<'a, K, V, M> Index<Handle> for Metas<'a, K, V, M> {
+    type Output = M;
+
+    fn index(&self, index: Handle) -> &Self::Output {
+        &self.0[index.slab][index.pos].meta
+    }
+}
+
+impl<'a, K, V, M> IndexMut<Handle> for Metas<'a, K, V, M> {
+    fn index_mut(&mut self, index: Handle) -> &mut Self::Output {
+        &mut self.0[index.slab][index.pos].meta
+    }
+}
+
+/// Timer wheel's view of the entries.
+struct Timers<'a, K, V, M>(&'a mut Slab<Slab<Entry<K, V, M>>>);
+
+impl<'a, K, V, M> Index<Pointer> for Timers<'a, K, V, M> {
+    type Output = TimerNode;
+
+    fn index(&self, index: Pointer) -> &Self::Output {
+        let Handle { slab, pos } = index.handle();
+        &self.0[slab][pos].timer
+    }
+}
+
+impl<'a, K, V, M> IndexMut<Pointer> for Timers<'a, K, V, M> {
+    fn index_mut(&mut self, index: Pointer) -> &mut Self::Output {
+        let Handle { slab, pos } = index.handle();
+        &mut self.0[slab][pos].timer
+    }
+}
+
+/// Simplifying read access to elements contained within.
+impl<K, V, P: EvictionPolicy> Index<Pointer> for Storage<K, V, P> {
+    type Output = Entry<K, V, P::Meta>;
+
+    fn index(&self, index: Pointer) -> &Self::Output {
+        if let Pointer::InternalPointer { slab, pos } = index {
+            self.slabs[slab].index(pos)
+        } else {
+            panic!("indexing on null pointer");
+        }
+    }
+}
+
+/// Simplifying write access to elements contained within.
+impl<K, V, P: EvictionPolicy> IndexMut<Pointer> for Storage<K, V, P> {
+    fn index_mut(&mut self, index: Pointer) -> &mut Self::Output {
+        if let Pointer::InternalPointer { slab, pos } = index {
+            self.slabs[slab].index_mut(pos)
+        } else {
+            panic!("indexing on null pointer");
+        }
+    }
+}
+
+impl<K, V, P: EvictionPolicy> Storage<K, V, P> {
+    pub(super) fn new(
+        cap: usize,
+        grow: bool,
+        max_weight: Option<usize>,
+        mut policy: P,
+        expiry: ExpiryMode,
+        jitter: Option<Jitter>,
+        clock: BoxClock,
+    ) -> Self {
+        policy.on_init(cap);
+        let mut slabs = Slab::new();
+        slabs.insert(Slab::with_capacity(cap));
+        Storage {
+            slabs,
+            policy,
+            cap,
+            len: 0,
+            timers: TimerWheel::new(),
+            origin: clock.now(),
+            clock,
+            grow,
+            expiry,
+            jitter,
+            grace: 0,
+            weight: 0,
+            max_weight,
+        }
+    }
+
+    /// Keep expired entry for given `grace`, served as stale, before it's removed.
+    pub(super) fn with_grace(mut self, grace: Duration) -> Self {
+        self.grace = ceil_millis(grace);
+        self
+    }
+
+    /// Returns whether reading an entry has to refresh its timestamp.
+    pub(super) fn tracks_access(&self) -> bool {
+        self.expiry.tracks_access()
+    }
+
+    /// Returns whether an entry with given `weight` could ever fit in the storage.
+    pub(super) fn admits(&self, weight: usize) -> bool {
+        self.max_weight
+            .map_or(true, |max_weight| weight <= max_weight)
+    }
+
+    /// Returns whether the entry at given pointer still fits in the storage
+    /// when its weight is replaced with given `weight`.
+    pub(super) fn fits(&self, ptr: Pointer, weight: usize) -> bool {
+        self.max_weight.map_or(true, |max_weight| {
+            self.weight - self[ptr].weight + weight <= max_weight
+        })
+    }
+
+    /// Remove expired entries, then victims chosen by policy,
+    /// until an entry with given `weight`, whose key has given `hash`, fits
+    /// in the storage. Returns removed key-value pairs.
+    pub(super) fn reserve(&mut self, weight: usize, hash: u64) -> Vec<(K, V)> {
+        let mut result = Vec::new();
+        let max_weight = match self.max_weight {
+            Some(max_weight) => max_weight,
+            None => return result,
+        };
+        let now = self.now();
+        while self.len > 0 && self.weight + weight > max_weight {
+            if let Some(ptr) = self.expired(now) {
+                result.push(self.remove(ptr));
+            } else if let Some(handle) = self.policy.victim(&mut Metas(&mut self.slabs), hash) {
+                result.push(self.take(handle.into()));
+            } else {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Insert a key-value, whose key has given `hash` and entry has given `weight`,
+    /// living for given `lifetime`.
+    /// return two data on a tuple
+    /// - new index,
+    /// - old pair key-value on update case or None on insert
+    ///
+    /// The entry which has expired the longest is reused, if any, otherwise the victim
+    /// chosen by policy is reused when storage is not allowed to grow
+    /// and already full.
+    pub(super) fn put(
+        &mut self,
+        key: K,
+        data: V,
+        hash: u64,
+        weight: Weight,
+        lifetime: Lifetime,
+    ) -> (Pointer, Option<(K, V)>) {
+        let now = self.now();
+        let ptr = if let Some(ptr) = self.expired(now) {
+            self.policy
+                .on_remove(&mut Metas(&mut self.slabs), ptr.handle());
+            Some(ptr)
+        } else if !self.grow && self.len >= self.cap {
+            self.policy
+                .victim(&mut Metas(&mut self.slabs), hash)
+                .map(Pointer::from)
+        } else {
+            None
+        };
+        if let Some(ptr) = ptr {
+            let top = &mut self[ptr];
+            let old_key = mem::replace(&mut top.key, key);
+            let old_data = mem::replace(&mut top.data, data);
+            let old_weight = mem::replace(&mut top.weight, weight.size);
+            top.meta = Default::default();
+            self.weight = self.weight - old_weight + weight.size;
+            self.write(ptr, now, lifetime);
+            self.policy
+                .on_insert(&mut Metas(&mut self.slabs), ptr.handle(), hash);
+            self.policy
+                .on_weigh(&mut Metas(&mut self.slabs), ptr.handle(), weight);
+            return (ptr, Some((old_key, old_data)));
+        }
+
+        // allocate new slab if necessary
+        let slab = {
+            let mut slab_id = None;
+            for (key, data) in self.slabs.iter() {
+                if data.len() < data.capacity() {
+                    slab_id = Some(key);
+                    break;
+                }
+            }
+            if let Some(slab_id) = slab_id {
+                slab_id
+            } else {
+                self.slabs.insert(Slab::with_capacity(self.cap))
+            }
+        };
+
+        // insert entry
+        let entry = Entry::new(key, data, weight.size);
+        let id = Pointer::InternalPointer {
+            slab,
+            pos: self.slabs[slab].insert(entry),
+        };
+        self.len += 1;
+        self.weight += weight.size;
+        self.write(id, now, lifetime);
+        self.policy
+            .on_insert(&mut Metas(&mut self.slabs), id.handle(), hash);
+        self.policy
+            .on_weigh(&mut Metas(&mut self.slabs), id.handle(), weight);
+        (id, None)
+    }
+
+    /// Update the data associated with given pointer, along with its weight,
+    /// living for given `lifetime`.
+    pub(super) fn update(
+        &mut self,
+        ptr: Pointer,
+        data: V,
+        weight: Weight,
+        lifetime: Lifetime,
+    ) -> V {
+        self.policy
+            .on_update(&mut Metas(&mut self.slabs), ptr.handle());
+        self.policy
+            .on_weigh(&mut Metas(&mut self.slabs), ptr.handle(), weight);
+        let top = &mut self[ptr];
+        let old_weight = mem::replace(&mut top.weight, weight.size);
+        let old_data = mem::replace(&mut top.data, data);
+        self.weight = self.weight - old_weight + weight.size;
+        self.write(ptr, self.now(), lifetime);
+        old_data
+    }
+
+    /// Return the data associated with given pointer, refreshing its timestamp.
+    /// Expired entry is returned as stale, untouched, within the grace,
+    /// or removed after.
+    pub(super) fn get(&mut self, ptr: Pointer) -> Option<Freshness<&V>> {
+        let now = self.now();
+        let expire_at = self[ptr].expire_at();
+        if expire_at.saturating_add(self.grace) <= now {
+            self.remove(ptr);
+            None
+        } else if expire_at <= now {
+            Some(Freshness::Stale(&self[ptr].data))
+        } else {
+            self.policy
+                .on_access(&mut Metas(&mut self.slabs), ptr.handle());
+            self.reindex(ptr, |entry| entry.timestamp = now);
+            Some(Freshness::Fresh(&self[ptr].data))
+        }
+    }
+
+    /// Return the data associated with given pointer without touching
+    /// its position, or `None` if it's already expired.
+    pub(super) fn peek(&self, ptr: Pointer) -> Option<&V> {
+        let entry = &self[ptr];
+        if entry.expire_at() <= self.now() {
+            None
+        } else {
+            Some(&entry.data)
+        }
+    }
+
+    /// Return the data associated with given pointer without touching
+    /// its position, notifying policy of the read, or `None` if it's already expired.
+    pub(super) fn read(&self, ptr: Pointer) -> Option<&V> {
+        let data = self.peek(ptr)?;
+        self.policy.on_shared_access(&self[ptr].meta);
+        Some(data)
+    }
+
+    /// Return the data associated with given pointer if it's expired,
+    /// but still within the grace.
+    pub(super) fn stale(&self, ptr: Pointer) -> Option<&V> {
+        let entry = &self[ptr];
+        let now = self.now();
+        if entry.expire_at() <= now && now < entry.expire_at().saturating_add(self.grace) {
+            Some(&entry.data)
+        } else {
+            None
+        }
+    }
+
+    /// Mark the expired entry at pointer as being reloaded, returns whether
+    /// it's not already, until it's written again.
+    #[cfg(feature = "asynchronous")]
+    pub(super) fn revalidate(&mut self, ptr: Pointer) -> bool {
+        !mem::replace(&mut self[ptr].revalidating, true)
+    }
+
+    /// Return the key-value pair at given pointer.
+    pub(super) fn pair(&self, ptr: Pointer) -> (&K, &V) {
+        let entry = &self[ptr];
+        (&entry.key, &entry.data)
+    }
+
+    /// Returns the duration left before the entry at pointer expires.
+    pub(super) fn remaining(&self, ptr: Pointer) -> Duration {
+        Duration::from_millis(self[ptr].expire_at().saturating_sub(self.now()))
+    }
+
+    /// Returns the duration left before the entry at pointer expires after its write,
+    /// or at its given time, if any, regardless of when it is last read.
+    pub(super) fn write_remaining(&self, ptr: Pointer) -> Option<Duration> {
+        match self[ptr].write_expire_at {
+            u64::MAX => None,
+            expire_at => Some(Duration::from_millis(expire_at.saturating_sub(self.now()))),
+        }
+    }
+
+    /// Returns the time taken to compute the data at pointer.
+    pub(super) fn compute_time(&self, ptr: Pointer) -> Duration {
+        Duration::from_millis(self[ptr].compute_millis)
+    }
+
+    /// Record the time taken to compute the data at pointer.
+    pub(super) fn set_compute_time(&mut self, ptr: Pointer, compute_time: Duration) {
+        self[ptr].compute_millis = ceil_millis(compute_time);
+    }
+
+    /// Expire the entry at pointer after given `duration` from now,
+    /// regardless of expiry mode, but no later than its deadline if it's given one.
+    pub(super) fn expire_after(&mut self, ptr: Pointer, duration: Duration) {
+        let expire_at = self.now().saturating_add(ceil_millis(duration));
+        self.reindex(ptr, |entry| {
+            entry.write_expire_at = expire_at.min(entry.deadline);
+            entry.idle_millis = None;
+        });
+    }
+
+    pub(super) fn evict(&mut self) -> Vec<K> {
+        let now = self.now();
+        let mut result = Vec::new();
+        while let Some(ptr) = self.expired(now) {
+            let (key, _) = self.remove(ptr);
+            result.push(key);
+        }
+        result
+    }
+
+    pub(super) fn capacity(&self) -> usize {
+        self.slabs.iter().map(|(_, slab)| slab.capacity()).sum()
+    }
+
+    pub(super) fn weight(&self) -> usize {
+        self.weight
+    }
+
+    #[cfg(test)]
+    pub(super) fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /// Returns the current instant of the clock.
+    #[cfg(feature = "asynchronous")]
+    pub(super) fn instant(&self) -> Instant {
+        self.clock.now()
+    }
+
+    /// Returns the earliest time an expired entry could be evicted, if any entry expires.
+    #[cfg(feature = "asynchronous")]
+    pub(super) fn next_expiry(&self) -> Option<Instant> {
+        self.timers
+            .next_deadline()
+            .and_then(|deadline| self.origin.checked_add(Duration::from_millis(deadline)))
+    }
+
+    /// Returns current time in milliseconds since storage is created.
+    #[inline]
+    fn now(&self) -> u64 {
+        self.clock
+            .now()
+            .saturating_duration_since(self.origin)
+            .as_millis() as u64
+    }
+
+    /// Returns the entry which has expired the longest, if any.
+    fn expired(&mut self, now: u64) -> Option<Pointer> {
+        self.timers.advance(&mut Timers(&mut self.slabs), now);
+        self.timers.expired()
+    }
+
+    /// Returns given duration in milliseconds, spread by jitter if any.
+    fn spread(&mut self, duration: Duration) -> u64 {
+        let millis = ceil_millis(duration);
+        match &mut self.jitter {
+            Some(jitter) => jitter.spread(millis),
+            None => millis,
+        }
+    }
+
+    /// Set the time the entry at pointer is written, along with the time
+    /// it expires according to given `lifetime`, spread by jitter unless
+    /// it's given a deadline.
+    fn write(&mut self, ptr: Pointer, now: u64, lifetime: Lifetime) {
+        let (write_expire_at, idle_millis, deadline) = match lifetime {
+            Lifetime::Until(deadline) => {
+                let deadline = ceil_millis(deadline.saturating_duration_since(self.origin));
+                (deadline, None, deadline)
+            }
+            Lifetime::Default | Lifetime::Ttl(_) => {
+                let (after_write, after_access) = self.expiry.lifetimes(lifetime.ttl());
+                let write_expire_at = match after_write {
+                    Some(after_write) => now.saturating_add(self.spread(after_write)),
+                    None => u64::MAX,
+                };
+                (
+                    write_expire_at,
+                    after_access.map(|after_access| self.spread(after_access)),
+                    u64::MAX,
+                )
+            }
+        };
+        self.reindex(ptr, |entry| {
+            entry.timestamp = now;
+            entry.write_expire_at = write_expire_at;
+            entry.idle_millis = idle_millis;
+            entry.deadline = deadline;
+            entry.revalidating = false;
+        });
+    }
+
+    /// Modify the entry at pointer, and schedule it by the time it expires,
+    /// along with the grace.
+    fn reindex(&mut self, ptr: Pointer, modify: impl FnOnce(&mut Entry<K, V, P::Meta>)) {
+        let entry = &mut self[ptr];
+        modify(entry);
+        let expire_at = entry.expire_at().saturating_add(self.grace);
+        self.timers
+            .schedule(&mut Timers(&mut self.slabs), ptr, expire_at);
+    }
+
+    /// Remove the entry at pointer, returning its key-value.
+    pub(super) fn remove(&mut self, ptr: Pointer) -> (K, V) {
+        self.policy
+            .on_remove(&mut Metas(&mut self.slabs), ptr.handle());
+        self.take(ptr)
+    }
+
+    /// Remove the entry at pointer, already detached from policy, returning its key-value.
+    fn take(&mut self, ptr: Pointer) -> (K, V) {
+        if let Pointer::InternalPointer { slab, pos } = ptr {
+            self.timers.cancel(&mut Timers(&mut self.slabs), ptr);
+            let entry = self.slabs[slab].remove(pos);
+            if self.slabs[slab].is_empty() {
+                self.slabs.remove(slab);
+            }
+            self.len -= 1;
+            self.weight -= entry.weight;
+            (entry.key, entry.data)
+        } else {
+            panic!("removing null pointer");
+        }
+    }
+}
@@ -0,0 +1,1491 @@
+use super::timer_wheel::{TimerNode, TimerWheel};
+use super::*;
+use crate::policy::Weight;
+#[cfg(feature = "asynchronous")]
+use crate::{FifoAsyncCache, LruAsyncCache};
+use crate::{FifoCache, LruCache, MruCache};
+use serde_json::{self, Value};
+#[cfg(feature = "asynchronous")]
+use std::sync::Arc;
+use std::{
+    ops::{Index, IndexMut},
+    rc::Rc,
+    time::Duration,
+};
+#[cfg(feature = "asynchronous")]
+use tokio::{task, time};
+
+#[test]
+#[should_panic]
+fn test_create_cache_with_cap_0() {
+    LruCache::<usize, ()>::new(0, 60);
+}
+
+#[test]
+fn test_get_on_empty_cache() {
+    let mut cache = LruCache::<(), usize>::new(1, 60);
+
+    assert!(cache.is_empty());
+    assert_eq!(cache.get(&()), None);
+}
+
+#[test]
+fn test_get_uncached_key() {
+    let mut cache = LruCache::<usize, usize>::new(1, 60);
+
+    cache.put(1, 1);
+
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.map[&1], Pointer::InternalPointer { slab: 0, pos: 0 });
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_reuse_single_entry() {
+    let mut cache = LruCache::<&str, Rc<Value>>::new(1, 60);
+
+    let val_1: Rc<Value> = Rc::new(serde_json::from_str(r#"{"id":1}"#).unwrap());
+    let val_2: Rc<Value> = Rc::new(serde_json::from_str(r#"{"id":2}"#).unwrap());
+
+    let old_value = cache.put("1", val_1.clone());
+    assert_eq!(old_value, None);
+
+    let old_value = cache.put("1", val_2.clone());
+    assert!(if let Some(value) = old_value {
+        value == val_1
+    } else {
+        false
+    });
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.capacity(), 1);
+    assert_eq!(
+        cache.map[&"1"],
+        Pointer::InternalPointer { slab: 0, pos: 0 }
+    );
+}
+
+#[test]
+fn test_reuse_expire_entry() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, Rc<Value>>::builder()
+        .multiply_cap(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build();
+
+    let val_1: Rc<Value> = Rc::new(serde_json::from_str(r#"{"id":1}"#).unwrap());
+    let val_2: Rc<Value> = Rc::new(serde_json::from_str(r#"{"id":2}"#).unwrap());
+
+    let old_value = cache.put(1, val_1.clone());
+    assert_eq!(old_value, None);
+
+    // expired entry of another key is reused, only reported by push
+    clock.advance(Duration::from_secs(1));
+    let removed = cache.push(2, val_2.clone());
+    assert!(if let Some((1, value)) = removed {
+        value == val_1
+    } else {
+        false
+    });
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.capacity(), 2);
+    assert_eq!(cache.map[&2], Pointer::InternalPointer { slab: 0, pos: 0 });
+}
+
+#[test]
+fn test_reuse_last_expire_entry() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, Rc<Value>>::builder()
+        .multiply_cap(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build();
+
+    let val_1: Rc<Value> = Rc::new(serde_json::from_str(r#"{"id":1}"#).unwrap());
+    let val_2: Rc<Value> = Rc::new(serde_json::from_str(r#"{"id":2}"#).unwrap());
+    let val_3: Rc<Value> = Rc::new(serde_json::from_str(r#"{"id":3}"#).unwrap());
+
+    let old_value = cache.put(1, val_1.clone());
+    assert_eq!(old_value, None);
+
+    let old_value = cache.put(2, val_2.clone());
+    assert_eq!(old_value, None);
+
+    clock.advance(Duration::from_secs(1));
+    let removed = cache.push(3, val_3.clone());
+    assert!(if let Some((1, value)) = removed {
+        value == val_1
+    } else {
+        false
+    });
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.capacity(), 2);
+    assert_eq!(cache.map[&3], Pointer::InternalPointer { slab: 0, pos: 0 });
+    assert_eq!(cache.map[&2], Pointer::InternalPointer { slab: 0, pos: 1 });
+}
+
+#[test]
+fn test_get_head_entry() {
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+
+    let cache_head = cache.get(&2);
+    assert_eq!(cache_head, Some(&"two"));
+    assert_eq!(cache.push(3, "three"), Some((1, "one")));
+    assert_eq!(cache.push(4, "four"), Some((2, "two")));
+}
+
+#[test]
+fn test_get_least_entry() {
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+
+    let cache_head = cache.get(&1);
+    assert_eq!(cache_head, Some(&"one"));
+    assert_eq!(cache.push(4, "four"), Some((2, "two")));
+    assert_eq!(cache.push(5, "five"), Some((3, "three")));
+    assert_eq!(cache.push(6, "six"), Some((1, "one")));
+}
+
+#[test]
+fn test_get_middle_entry() {
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+
+    let cache_head = cache.get(&2);
+    assert_eq!(cache_head, Some(&"two"));
+    assert_eq!(cache.push(4, "four"), Some((1, "one")));
+    assert_eq!(cache.push(5, "five"), Some((3, "three")));
+    assert_eq!(cache.push(6, "six"), Some((2, "two")));
+}
+
+#[test]
+fn test_get_expire_entry() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+
+    let cache_head = cache.get(&2);
+    assert_eq!(cache_head, Some(&"two"));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.map[&1], Pointer::InternalPointer { slab: 0, pos: 0 });
+    assert_eq!(cache.map[&3], Pointer::InternalPointer { slab: 1, pos: 0 });
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.capacity(), 4);
+}
+
+#[test]
+#[should_panic]
+fn test_build_cache_with_max_capacity_0() {
+    LruCache::<usize, ()>::builder()
+        .max_capacity(0)
+        .timeout_secs(60)
+        .build();
+}
+
+#[test]
+fn test_bounded_evict_least_recently_used() {
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build();
+
+    assert_eq!(cache.push(1, "one"), None);
+    assert_eq!(cache.push(2, "two"), None);
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.push(3, "three"), Some((2, "two")));
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.capacity(), 2);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.map[&3], Pointer::InternalPointer { slab: 0, pos: 1 });
+    assert_eq!(cache.push(4, "four"), Some((1, "one")));
+}
+
+#[test]
+fn test_bounded_reuse_after_expire() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.push(3, "three"), Some((2, "two")));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.push(4, "four"), None);
+    assert_eq!(cache.push(5, "five"), Some((3, "three")));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.capacity(), 2);
+}
+
+#[test]
+fn test_bounded_put_return_old_value_of_same_key() {
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(1)
+        .timeout_secs(60)
+        .build();
+
+    assert_eq!(cache.put(1, "a"), None);
+    assert_eq!(cache.put(2, "b"), None);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.put(2, "beta"), Some("b"));
+    assert_eq!(
+        cache.put_with_compute_time(3, "c", Duration::from_secs(1)),
+        None
+    );
+    assert_eq!(cache.push(2, "b"), Some((3, "c")));
+}
+
+#[test]
+fn test_put_with_ttl_evict_not_at_tail() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .timeout_secs(60)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put_with_ttl(2, "two", Duration::from_secs(1));
+    cache.put(3, "three");
+
+    clock.advance(Duration::from_secs(1));
+    cache.evict();
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&3), Some(&"three"));
+}
+
+#[test]
+fn test_put_with_ttl_bounded_reuse_expired_not_at_tail() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put_with_ttl(2, "two", Duration::from_millis(500));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.push(3, "three"), Some((2, "two")));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&3), Some(&"three"));
+}
+
+#[test]
+fn test_put_with_ttl_get_refresh_same_ttl() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build();
+
+    cache.put_with_ttl(1, "one", Duration::from_secs(2));
+    cache.put(2, "two");
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), None);
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), Some(&"one"));
+}
+
+#[test]
+fn test_put_without_ttl_restore_timeout() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .timeout_secs(60)
+        .clock(clock.clone())
+        .build();
+
+    cache.put_with_ttl(1, "one", Duration::from_secs(1));
+    assert_eq!(cache.put(1, "uno"), Some("one"));
+
+    clock.advance(Duration::from_secs(1));
+    cache.evict();
+    assert_eq!(cache.get(&1), Some(&"uno"));
+}
+
+#[test]
+fn test_put_until_evict_not_at_tail() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .timeout_secs(60)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put_until(2, "two", clock.now() + Duration::from_secs(10));
+    cache.put(3, "three");
+
+    // reading doesn't extend the deadline
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(cache.get(&2), Some(&"two"));
+
+    clock.advance(Duration::from_secs(5));
+    cache.evict();
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&3), Some(&"three"));
+}
+
+#[test]
+fn test_put_until_past_deadline() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .clock(clock.clone())
+        .build();
+
+    let deadline = clock.now();
+    clock.advance(Duration::from_secs(1));
+    cache.put_until(1, "one", deadline);
+    assert_eq!(cache.get(&1), None);
+}
+
+#[test]
+fn test_put_without_deadline_restore_timeout() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(60)))
+        .clock(clock.clone())
+        .build();
+
+    cache.put_until(1, "one", clock.now() + Duration::from_secs(1));
+    assert_eq!(cache.put(1, "uno"), Some("one"));
+
+    clock.advance(Duration::from_secs(1));
+    cache.evict();
+    assert_eq!(cache.get(&1), Some(&"uno"));
+}
+
+#[test]
+fn test_expire_after_write_not_refreshed_on_get() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(2)))
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.put(2, "deux"), Some("two"));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some(&"deux"));
+    cache.evict();
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_expire_after_both_write_and_access() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .expiry_mode(ExpiryMode::Both {
+            after_write: Duration::from_secs(3),
+            after_access: Duration::from_secs(2),
+        })
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), Some(&"one"));
+
+    // idle entry expires
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), None);
+
+    // hot entry expires no later than after write
+    clock.advance(Duration::from_secs(1));
+    cache.evict();
+    assert_eq!(cache.len(), 0);
+}
+
+#[test]
+fn test_expire_after_write_put_with_ttl() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(60)))
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put_with_ttl(2, "two", Duration::from_secs(1));
+    assert_eq!(cache.get(&2), Some(&"two"));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), None);
+}
+
+#[test]
+fn test_timeout_sub_second() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout(Duration::from_millis(300))
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    clock.advance(Duration::from_millis(150));
+    assert_eq!(cache.get(&1), Some(&"one"));
+
+    // refreshed by the read above
+    clock.advance(Duration::from_millis(200));
+    assert_eq!(cache.get(&1), Some(&"one"));
+
+    clock.advance(Duration::from_millis(350));
+    assert_eq!(cache.get(&1), None);
+}
+
+#[test]
+fn test_put_with_ttl_sub_second() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(60)))
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put_with_ttl(2, "two", Duration::from_millis(100));
+    assert_eq!(cache.get(&2), Some(&"two"));
+
+    clock.advance(Duration::from_millis(150));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), None);
+}
+
+#[test]
+fn test_bounded_without_timeout_never_expires() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put_with_ttl(3, "three", Duration::from_secs(1));
+    assert_eq!(cache.get(&1), None);
+
+    clock.advance(Duration::from_secs(365 * 24 * 60 * 60));
+    cache.evict();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&2), Some(&"two"));
+    assert_eq!(cache.get(&3), None);
+    assert_eq!(cache.push(4, "four"), None);
+    assert_eq!(cache.push(5, "five"), Some((2, "two")));
+}
+
+#[test]
+fn test_zero_timeout_never_expires() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .timeout_secs(0)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put_with_ttl(2, "two", Duration::from_secs(1));
+
+    clock.advance(Duration::from_secs(365 * 24 * 60 * 60));
+    cache.evict();
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.len(), 1);
+}
+
+/// Entry lives for the seconds carried by its value.
+struct ValueSecs;
+
+impl Expiry<usize, u64> for ValueSecs {
+    fn expire_after_create(&self, _: &usize, value: &u64) -> Duration {
+        Duration::from_secs(*value)
+    }
+}
+
+/// Entry lives for 1 second, kept on update and extended to a minute on read.
+struct ExtendOnRead;
+
+impl Expiry<usize, &str> for ExtendOnRead {
+    fn expire_after_create(&self, _: &usize, _: &&str) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn expire_after_update(&self, _: &usize, _: &&str, remaining: Duration) -> Duration {
+        remaining
+    }
+
+    fn expire_after_read(&self, _: &usize, _: &&str, _: Duration) -> Duration {
+        Duration::from_secs(60)
+    }
+}
+
+#[test]
+fn test_expiry_after_create_per_entry() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, u64>::builder()
+        .max_capacity(4)
+        .expiry(ValueSecs)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, 1);
+    cache.put(2, 60);
+    // update is treated as creation by default
+    cache.put(3, 60);
+    cache.put(3, 1);
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some(&60));
+    assert_eq!(cache.get(&3), None);
+}
+
+#[test]
+fn test_expiry_after_update_and_read() {
+    let clock = MockClock::new();
+    let mut cache = FifoCache::<usize, &str>::builder()
+        .max_capacity(4)
+        .expiry(ExtendOnRead)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.put(2, "deux"), Some("two"));
+    // peek doesn't count as read
+    assert_eq!(cache.peek(&2), Some(&"deux"));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), None);
+}
+
+#[test]
+fn test_expiry_put_with_ttl_overrides() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, u64>::builder()
+        .max_capacity(2)
+        .expiry(ValueSecs)
+        .clock(clock.clone())
+        .build();
+
+    cache.put_with_ttl(1, 60, Duration::from_secs(1));
+    cache.put_with_ttl(2, 1, Duration::from_secs(60));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some(&1));
+}
+
+#[test]
+fn test_expiry_read_keeps_put_until_deadline() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .expiry(ExtendOnRead)
+        .clock(clock.clone())
+        .build();
+
+    cache.put_until(1, "one", clock.now() + Duration::from_secs(5));
+    cache.put(2, "two");
+    // read extends entry to a minute, but never past its deadline
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), Some(&"two"));
+
+    clock.advance(Duration::from_secs(6));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some(&"two"));
+}
+
+/// Returns keys still in the cache after `elapsed` since they're put together,
+/// with their time-to-live spread by jitter seeded with `seed`.
+fn jittered_survivors(seed: u64, elapsed: Duration) -> Vec<usize> {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, usize>::builder()
+        .multiply_cap(256)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(100)))
+        .ttl_jitter_percent(10)
+        .jitter_seed(seed)
+        .clock(clock.clone())
+        .build();
+
+    for key in 0..1000 {
+        cache.put(key, key);
+    }
+    clock.advance(elapsed);
+    cache.evict();
+    (0..1000).filter(|key| cache.peek(key).is_some()).collect()
+}
+
+#[test]
+fn test_ttl_jitter_spread_within_percent() {
+    assert_eq!(
+        jittered_survivors(7, Duration::from_millis(89_999)).len(),
+        1000
+    );
+    assert!(jittered_survivors(7, Duration::from_secs(110)).is_empty());
+
+    let survivors = jittered_survivors(7, Duration::from_secs(100)).len();
+    assert!(300 < survivors && survivors < 700);
+}
+
+#[test]
+fn test_ttl_jitter_reproducible_by_seed() {
+    let elapsed = Duration::from_secs(100);
+    assert_eq!(
+        jittered_survivors(42, elapsed),
+        jittered_survivors(42, elapsed)
+    );
+    assert_ne!(
+        jittered_survivors(42, elapsed),
+        jittered_survivors(43, elapsed)
+    );
+}
+
+#[test]
+fn test_ttl_jitter_on_update_not_deadline() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(100)))
+        .ttl_jitter_percent(100)
+        .jitter_seed(1)
+        .clock(clock.clone())
+        .build();
+
+    cache.put_until(1, "one", clock.now() + Duration::from_secs(100));
+    cache.put(2, "two");
+    cache.put_with_ttl(2, "deux", Duration::from_secs(1000));
+
+    clock.advance(Duration::from_millis(99_999));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(cache.get(&1), None);
+
+    // updated time-to-live is spread between 0 and 2000 seconds
+    let expires_at = clock.now() + cache.storage.remaining(cache.map[&2]);
+    assert_ne!(expires_at, clock.now() + Duration::from_secs(900));
+}
+
+#[test]
+#[should_panic]
+fn test_ttl_jitter_more_than_100_percent() {
+    LruCache::<usize, ()>::builder()
+        .max_capacity(2)
+        .ttl_jitter_percent(101);
+}
+
+/// Returns how many of 1000 reads hint to refresh the entry taking `compute_time`,
+/// `remaining` before it expires.
+fn refresh_hints(beta: f64, compute_time: Duration, remaining: Duration) -> usize {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, usize>::builder()
+        .max_capacity(1)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(60)))
+        .refresh_beta(beta)
+        .jitter_seed(7)
+        .clock(clock.clone())
+        .build();
+
+    cache.put_with_compute_time(1, 1, compute_time);
+    clock.advance(Duration::from_secs(60) - remaining);
+    (0..1000)
+        .filter(|_| cache.get_with_refresh_hint(&1) == Some((&1, true)))
+        .count()
+}
+
+#[test]
+fn test_refresh_hint_rises_near_expiry() {
+    let compute_time = Duration::from_secs(10);
+    // hinted with probability of e^(-remaining / (beta * compute_time))
+    assert!(refresh_hints(1.0, compute_time, Duration::from_secs(60)) < 10);
+    let far = refresh_hints(1.0, compute_time, Duration::from_secs(30));
+    let near = refresh_hints(1.0, compute_time, Duration::from_secs(5));
+    assert!(far < 100 && 500 < near && near < 700);
+    assert!(refresh_hints(1.0, compute_time, Duration::from_millis(1)) > 990);
+
+    assert!(refresh_hints(4.0, compute_time, Duration::from_secs(30)) > 400);
+}
+
+#[test]
+fn test_refresh_hint_without_compute_time() {
+    let remaining = Duration::from_millis(1);
+    assert_eq!(refresh_hints(1.0, Duration::from_secs(0), remaining), 0);
+    assert_eq!(refresh_hints(0.0, Duration::from_secs(10), remaining), 0);
+
+    let mut cache = LruCache::<usize, usize>::new(1, 60);
+    cache.put_with_compute_time(1, 1, Duration::from_secs(10));
+    cache.put(1, 2);
+    assert_eq!(cache.get_with_refresh_hint(&1), Some((&2, false)));
+    assert_eq!(cache.get_with_refresh_hint(&2), None);
+}
+
+#[test]
+fn test_refresh_hint_only_expire_after_access() {
+    let mut cache = LruCache::<usize, usize>::new(1, 60);
+
+    // reading keeps pushing back the idle deadline, however long it takes to compute
+    cache.put_with_compute_time(1, 1, Duration::from_secs(600));
+    for _ in 0..1000 {
+        assert_eq!(cache.get_with_refresh_hint(&1), Some((&1, false)));
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_refresh_beta_negative() {
+    LruCache::<usize, ()>::builder()
+        .max_capacity(2)
+        .refresh_beta(-1.0);
+}
+
+#[test]
+fn test_get_or_stale_within_grace() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .stale_while_revalidate(Duration::from_secs(30))
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    assert_eq!(cache.get_or_stale(&1), Some(Freshness::Fresh(&"one")));
+
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.peek(&1), None);
+    cache.evict();
+    assert_eq!(cache.len(), 2);
+
+    // reading stale entry doesn't refresh it
+    clock.advance(Duration::from_millis(29_999));
+    assert_eq!(cache.get_or_stale(&1), Some(Freshness::Stale(&"one")));
+    assert_eq!(cache.get_or_stale(&2), Some(Freshness::Stale(&"two")));
+    cache.put(2, "deux");
+
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(cache.get_or_stale(&1), None);
+    assert_eq!(cache.get_or_stale(&2), Some(Freshness::Fresh(&"deux")));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_get_or_stale_without_grace() {
+    let clock = MockClock::new();
+    let mut cache = FifoCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    assert_eq!(cache.get_or_stale(&1), Some(Freshness::Fresh(&"one")));
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(cache.get_or_stale(&1), None);
+}
+
+#[test]
+fn test_evict_stale_after_grace() {
+    let clock = MockClock::new();
+    let mut cache = FifoCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(60)))
+        .stale_while_revalidate(Duration::from_secs(30))
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get_or_stale(&1), Some(Freshness::Stale(&"one")));
+
+    clock.advance(Duration::from_secs(30));
+    assert_eq!(cache.get_or_stale(&1), None);
+    assert_eq!(cache.len(), 1);
+    cache.evict();
+    assert!(cache.is_empty());
+}
+
+/// Timer nodes standing alone, indexed by position of the pointer.
+struct Nodes(Vec<TimerNode>);
+
+impl Index<Pointer> for Nodes {
+    type Output = TimerNode;
+
+    fn index(&self, index: Pointer) -> &Self::Output {
+        match index {
+            Pointer::InternalPointer { pos, .. } => &self.0[pos],
+            Pointer::Null => panic!("indexing on null pointer"),
+        }
+    }
+}
+
+impl IndexMut<Pointer> for Nodes {
+    fn index_mut(&mut self, index: Pointer) -> &mut Self::Output {
+        match index {
+            Pointer::InternalPointer { pos, .. } => &mut self.0[pos],
+            Pointer::Null => panic!("indexing on null pointer"),
+        }
+    }
+}
+
+fn node(pos: usize) -> Pointer {
+    Pointer::InternalPointer { slab: 0, pos }
+}
+
+/// Returns pseudo-random time-to-live in milliseconds, from milliseconds up to
+/// beyond the range of the timer wheel, seeded by `state`.
+fn mixed_ttl(state: &mut u64) -> u64 {
+    // xorshift
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    let bound = match *state % 5 {
+        0 => 1_000,
+        1 => 60 * 1_000,
+        2 => 60 * 60 * 1_000,
+        3 => 30 * 24 * 60 * 60 * 1_000,
+        _ => 3 * 365 * 24 * 60 * 60 * 1_000,
+    };
+    1 + (*state >> 8) % bound
+}
+
+#[test]
+fn test_timer_wheel_expire_exactly() {
+    let mut nodes = Nodes((0..4).map(|_| TimerNode::default()).collect());
+    let mut wheel = TimerWheel::new();
+
+    wheel.schedule(&mut nodes, node(0), 100);
+    wheel.schedule(&mut nodes, node(1), 5_000);
+    wheel.schedule(&mut nodes, node(2), 70);
+    wheel.schedule(&mut nodes, node(3), u64::MAX);
+    wheel.schedule(&mut nodes, node(2), 200);
+    wheel.cancel(&mut nodes, node(1));
+
+    wheel.advance(&mut nodes, 99);
+    assert!(wheel.expired().is_none());
+
+    wheel.advance(&mut nodes, 100);
+    assert!(wheel.expired() == Some(node(0)));
+    wheel.cancel(&mut nodes, node(0));
+    assert!(wheel.expired().is_none());
+
+    wheel.advance(&mut nodes, 1_000_000);
+    assert!(wheel.expired() == Some(node(2)));
+    wheel.cancel(&mut nodes, node(2));
+    assert!(wheel.expired().is_none());
+
+    // scheduled in the past
+    wheel.schedule(&mut nodes, node(1), 10);
+    assert!(wheel.expired() == Some(node(1)));
+}
+
+#[cfg(feature = "asynchronous")]
+#[test]
+fn test_timer_wheel_next_deadline() {
+    let mut nodes = Nodes((0..2).map(|_| TimerNode::default()).collect());
+    let mut wheel = TimerWheel::new();
+    assert_eq!(wheel.next_deadline(), None);
+
+    wheel.schedule(&mut nodes, node(0), 5_000);
+    wheel.schedule(&mut nodes, node(1), 100);
+    // following the deadline, which is the start of upper level slot at first,
+    // reaches the earliest entry, never past it
+    let mut now = 0;
+    while wheel.expired().is_none() {
+        now = wheel.next_deadline().unwrap();
+        assert!(now <= 100);
+        wheel.advance(&mut nodes, now);
+    }
+    assert_eq!(now, 100);
+    assert!(wheel.expired() == Some(node(1)));
+    // until it's taken off
+    assert_eq!(wheel.next_deadline(), Some(100));
+
+    wheel.cancel(&mut nodes, node(1));
+    let next = wheel.next_deadline().unwrap();
+    assert!(100 < next && next <= 5_000);
+}
+
+#[test]
+fn test_timer_wheel_millions_mixed_ttl() {
+    let count = 2_000_000;
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    let mut nodes = Nodes((0..count).map(|_| TimerNode::default()).collect());
+    let mut wheel = TimerWheel::new();
+
+    let mut deadlines = Vec::with_capacity(count);
+    for pos in 0..count {
+        let when = mixed_ttl(&mut state);
+        wheel.schedule(&mut nodes, node(pos), when);
+        deadlines.push(when);
+    }
+    // cancel some, and reschedule some others
+    for pos in (0..count).step_by(10) {
+        wheel.cancel(&mut nodes, node(pos));
+        deadlines[pos] = u64::MAX;
+    }
+    for pos in (5..count).step_by(10) {
+        let when = mixed_ttl(&mut state);
+        wheel.schedule(&mut nodes, node(pos), when);
+        deadlines[pos] = when;
+    }
+    let mut sorted = deadlines.clone();
+    sorted.sort_unstable();
+
+    let mut now = 0;
+    let mut expired = 0;
+    while expired < count - count / 10 {
+        now += 1 + now / 2;
+        wheel.advance(&mut nodes, now);
+        while let Some(ptr) = wheel.expired() {
+            if let Pointer::InternalPointer { pos, .. } = ptr {
+                assert!(deadlines[pos] <= now);
+            }
+            wheel.cancel(&mut nodes, ptr);
+            expired += 1;
+        }
+        assert_eq!(expired, sorted.partition_point(|&when| when <= now));
+    }
+}
+
+#[test]
+fn test_evict_mixed_ttl() {
+    let count = 250_000;
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, ()>::builder()
+        .multiply_cap(1 << 16)
+        .clock(clock.clone())
+        .build();
+
+    let mut ttls = Vec::with_capacity(count);
+    for key in 0..count {
+        let ttl = mixed_ttl(&mut state);
+        cache.put_with_ttl(key, (), Duration::from_millis(ttl));
+        ttls.push(ttl);
+    }
+    ttls.sort_unstable();
+
+    let mut elapsed = 0;
+    while !cache.is_empty() {
+        let step = 1 + elapsed / 2;
+        clock.advance(Duration::from_millis(step));
+        elapsed += step;
+        cache.evict();
+        assert_eq!(
+            cache.len(),
+            count - ttls.partition_point(|&ttl| ttl <= elapsed)
+        );
+    }
+    assert_eq!(cache.capacity(), 0);
+}
+
+fn weigh_len(_: &usize, value: &&str) -> Weight {
+    Weight {
+        cost: 1,
+        size: value.len(),
+    }
+}
+
+#[test]
+fn test_weighted_evict_until_fit() {
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .max_weight(12)
+        .weigher(weigh_len)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    assert_eq!(cache.weight(), 11);
+    assert_eq!(cache.len(), 3);
+
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(
+        cache.try_push(4, "sevenly"),
+        Ok(vec![(2, "two"), (3, "three")])
+    );
+    assert_eq!(cache.weight(), 10);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&4), Some(&"sevenly"));
+}
+
+#[test]
+fn test_weighted_reject_overweight() {
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .max_weight(4)
+        .weigher(weigh_len)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    assert_eq!(
+        cache.try_push(2, "three"),
+        Err(Overweight {
+            key: 2,
+            value: "three"
+        })
+    );
+    assert_eq!(cache.push(2, "three"), Some((2, "three")));
+
+    // rejection isn't mistaken for the old value
+    assert_eq!(cache.put(1, "three"), None);
+    assert_eq!(cache.put_with_ttl(1, "three", Duration::from_secs(1)), None);
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.weight(), 3);
+}
+
+#[test]
+fn test_weighted_put_drop_overweight() {
+    let clock = MockClock::new();
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .max_weight(4)
+        .weigher(weigh_len)
+        .timeout_secs(60)
+        .clock(clock.clone())
+        .build();
+    let deadline = clock.now() + Duration::from_secs(1);
+
+    // new entry is dropped
+    assert_eq!(cache.put(1, "three"), None);
+    assert_eq!(cache.put_with_ttl(1, "three", Duration::from_secs(1)), None);
+    assert_eq!(cache.put_until(1, "three", deadline), None);
+    assert_eq!(
+        cache.put_with_compute_time(1, "three", Duration::from_secs(1)),
+        None
+    );
+    assert_eq!(cache.get(&1), None);
+    assert!(cache.is_empty());
+
+    // update is dropped, keeping the old value
+    cache.put(2, "two");
+    assert_eq!(cache.put(2, "three"), None);
+    assert_eq!(cache.put_with_ttl(2, "three", Duration::from_secs(1)), None);
+    assert_eq!(cache.put_until(2, "three", deadline), None);
+    assert_eq!(
+        cache.put_with_compute_time(2, "three", Duration::from_secs(1)),
+        None
+    );
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&2), Some(&"two"));
+    assert_eq!(cache.weight(), 3);
+}
+
+#[test]
+fn test_weighted_update_heavier_value() {
+    let mut cache = LruCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .max_weight(8)
+        .weigher(weigh_len)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    assert_eq!(cache.try_push(2, "deux"), Ok(vec![(2, "two")]));
+    assert_eq!(cache.weight(), 7);
+
+    // the updated entry itself is never evicted
+    assert_eq!(
+        cache.try_push(2, "zweizwei"),
+        Ok(vec![(2, "deux"), (1, "one")])
+    );
+    assert_eq!(cache.weight(), 8);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&2), Some(&"zweizwei"));
+}
+
+#[test]
+fn test_weighted_bounded_evict_expired_first() {
+    let clock = MockClock::new();
+    let mut cache = MruCache::<usize, &str>::builder()
+        .max_capacity(4)
+        .max_weight(8)
+        .weigher(weigh_len)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "tw");
+    clock.advance(Duration::from_secs(1));
+    cache.put(2, "two");
+
+    // expired entry is removed instead of the most recently used one
+    assert_eq!(cache.try_push(3, "four"), Ok(vec![(1, "one")]));
+    assert_eq!(cache.weight(), 7);
+    assert_eq!(cache.get(&2), Some(&"two"));
+}
+
+/// Move both mock clock and paused tokio's timer forward,
+/// letting runtime daemon evict expired entries.
+#[cfg(feature = "asynchronous")]
+async fn advance_async(clock: &MockClock, duration: Duration) {
+    // runtime daemon should be waiting on tokio's timer before it moves
+    let () = task::yield_now().await;
+    clock.advance(duration);
+    time::advance(duration).await;
+    let () = task::yield_now().await;
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_get_expire_entry_async() {
+    time::pause();
+    let clock = MockClock::new();
+    let cache = LruAsyncCache::<usize, Arc<Value>>::builder()
+        .multiply_cap(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build_async();
+
+    let val_1: Arc<Value> = Arc::new(serde_json::from_str(r#"{"id":1}"#).unwrap());
+    let val_2: Arc<Value> = Arc::new(serde_json::from_str(r#"{"id":2}"#).unwrap());
+    let val_3: Arc<Value> = Arc::new(serde_json::from_str(r#"{"id":3}"#).unwrap());
+
+    cache.put(1, val_1.clone()).await;
+    cache.put(2, val_2.clone()).await;
+    cache.put(3, val_3.clone()).await;
+
+    assert!(if let Some(value) = cache.get(&2).await {
+        value == val_2
+    } else {
+        false
+    });
+
+    advance_async(&clock, Duration::from_secs(1)).await;
+    assert_eq!(cache.len().await, 0);
+    assert_eq!(cache.capacity().await, 0);
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_without_timeout_no_evictor_async() {
+    let cache = LruAsyncCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .build_async();
+    assert_eq!(Arc::strong_count(&cache), 1);
+
+    // zero timeout never expires entry, without evictor either
+    let cache = LruAsyncCache::<usize, &str>::new(2, 0);
+    assert_eq!(Arc::strong_count(&cache), 1);
+    cache.put(1, "one").await;
+    assert_eq!(cache.get(&1).await, Some("one"));
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_bounded_push_async() {
+    let cache = LruAsyncCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build_async();
+
+    assert_eq!(cache.push(1, "one").await, None);
+    assert_eq!(cache.push(2, "two").await, None);
+    assert_eq!(cache.get(&1).await, Some("one"));
+    assert_eq!(cache.push(3, "three").await, Some((2, "two")));
+    assert_eq!(cache.len().await, 2);
+    assert_eq!(cache.capacity().await, 2);
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_weighted_try_push_async() {
+    let cache = LruAsyncCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .max_weight(6)
+        .weigher(weigh_len)
+        .timeout_secs(60)
+        .build_async();
+
+    assert_eq!(cache.try_push(1, "one").await, Ok(vec![]));
+    assert_eq!(cache.try_push(2, "two").await, Ok(vec![]));
+    assert_eq!(
+        cache.try_push(3, "three").await,
+        Ok(vec![(1, "one"), (2, "two")])
+    );
+    assert_eq!(
+        cache.try_push(4, "quatre!").await,
+        Err(Overweight {
+            key: 4,
+            value: "quatre!"
+        })
+    );
+    assert_eq!(cache.weight().await, 5);
+    assert_eq!(cache.len().await, 1);
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_expiry_after_read_async() {
+    time::pause();
+    let clock = MockClock::new();
+    let cache = FifoAsyncCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .expiry(ExtendOnRead)
+        .clock(clock.clone())
+        .build_async();
+
+    cache.put(1, "one").await;
+    cache.put(2, "two").await;
+    assert_eq!(cache.get(&1).await, Some("one"));
+
+    advance_async(&clock, Duration::from_secs(1)).await;
+    assert_eq!(cache.get(&1).await, Some("one"));
+    assert_eq!(cache.len().await, 1);
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_put_until_evicted_by_daemon_async() {
+    time::pause();
+    let clock = MockClock::new();
+    let cache = LruAsyncCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build_async();
+
+    cache
+        .put_until(1, "one", clock.now() + Duration::from_secs(1))
+        .await;
+    cache
+        .put_until(2, "two", clock.now() + Duration::from_secs(60))
+        .await;
+    assert_eq!(cache.get(&1).await, Some("one"));
+
+    advance_async(&clock, Duration::from_secs(1)).await;
+    assert_eq!(cache.len().await, 1);
+    assert_eq!(cache.get(&2).await, Some("two"));
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_daemon_wakes_on_earlier_expiry_async() {
+    time::pause();
+    let clock = MockClock::new();
+    let cache = LruAsyncCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .clock(clock.clone())
+        .build_async();
+
+    // runtime daemon waits for a minute, until it's woken by earlier entry
+    cache.put(1, "one").await;
+    advance_async(&clock, Duration::from_secs(1)).await;
+    cache.put_with_ttl(2, "two", Duration::from_secs(1)).await;
+
+    advance_async(&clock, Duration::from_secs(1)).await;
+    assert_eq!(cache.len().await, 1);
+    assert_eq!(cache.get(&1).await, Some("one"));
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_daemon_without_timeout_async() {
+    time::pause();
+    let clock = MockClock::new();
+    let cache = LruAsyncCache::<usize, &str>::builder()
+        .max_capacity(4)
+        .clock(clock.clone())
+        .build_async();
+
+    cache.put(1, "one").await;
+    cache.put_with_ttl(2, "two", Duration::from_secs(1)).await;
+    cache
+        .put_until(3, "three", clock.now() + Duration::from_secs(2))
+        .await;
+
+    advance_async(&clock, Duration::from_secs(1)).await;
+    assert_eq!(cache.len().await, 2);
+    advance_async(&clock, Duration::from_secs(1)).await;
+    assert_eq!(cache.len().await, 1);
+    assert_eq!(cache.get(&1).await, Some("one"));
+
+    // runtime daemon doesn't hold the cache
+    assert_eq!(Arc::strong_count(&cache), 1);
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_ttl_jitter_async() {
+    time::pause();
+    let clock = MockClock::new();
+    let cache = LruAsyncCache::<usize, usize>::builder()
+        .multiply_cap(256)
+        .timeout_secs(100)
+        .ttl_jitter_percent(10)
+        .jitter_seed(7)
+        .clock(clock.clone())
+        .build_async();
+
+    for key in 0..1000 {
+        cache.put(key, key).await;
+    }
+    advance_async(&clock, Duration::from_secs(100)).await;
+    let survivors = cache.len().await;
+    assert!(300 < survivors && survivors < 700);
+
+    advance_async(&clock, Duration::from_secs(100)).await;
+    assert_eq!(cache.len().await, 0);
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_get_or_load_refresh_early_async() {
+    time::pause();
+    let clock = MockClock::new();
+    let cache = LruAsyncCache::<usize, usize>::builder()
+        .max_capacity(2)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(60)))
+        .jitter_seed(7)
+        .clock(clock.clone())
+        .build_async();
+
+    let value = cache
+        .get_or_load(1, || async {
+            clock.advance(Duration::from_secs(10));
+            1
+        })
+        .await;
+    assert_eq!(value, 1);
+    assert_eq!(cache.get_or_load(1, || async { 2 }).await, 1);
+
+    // reloaded before it expires, as it took 10 seconds to load
+    advance_async(&clock, Duration::from_millis(59_990)).await;
+    let mut loads = 0;
+    while cache.get_or_load(1, || async { 2 }).await == 1 {
+        loads += 1;
+        assert!(loads < 10);
+    }
+    assert_eq!(cache.peek(&1).await, Some(2));
+}
+
+#[cfg(feature = "asynchronous")]
+#[tokio::test]
+async fn test_get_or_revalidate_single_reload_async() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    time::pause();
+    let clock = MockClock::new();
+    let cache = LruAsyncCache::<usize, usize>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .stale_while_revalidate(Duration::from_secs(30))
+        .clock(clock.clone())
+        .build_async();
+    let loads = Arc::new(AtomicUsize::new(0));
+    let load = |value| {
+        let loads = loads.clone();
+        move || {
+            loads.fetch_add(1, Ordering::SeqCst);
+            async move { value }
+        }
+    };
+
+    assert_eq!(
+        cache.get_or_revalidate(1, load(1)).await,
+        Freshness::Fresh(1)
+    );
+    assert_eq!(
+        cache.get_or_revalidate(1, load(2)).await,
+        Freshness::Fresh(1)
+    );
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    advance_async(&clock, Duration::from_secs(60)).await;
+    assert_eq!(cache.get_or_stale(&1).await, Some(Freshness::Stale(1)));
+    assert_eq!(
+        cache.get_or_revalidate(1, load(2)).await,
+        Freshness::Stale(1)
+    );
+    assert_eq!(
+        cache.get_or_revalidate(1, load(3)).await,
+        Freshness::Stale(1)
+    );
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+
+    // reloaded in the background
+    let () = task::yield_now().await;
+    assert_eq!(
+        cache.get_or_revalidate(1, load(4)).await,
+        Freshness::Fresh(2)
+    );
+
+    // loaded right away beyond the grace
+    advance_async(&clock, Duration::from_secs(90)).await;
+    assert_eq!(cache.get_or_stale(&1).await, None);
+    assert_eq!(
+        cache.get_or_revalidate(1, load(5)).await,
+        Freshness::Fresh(5)
+    );
+    assert_eq!(loads.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,274 @@
+use super::storage::Pointer;
+use std::ops::IndexMut;
+
+const SLOT_BITS: usize = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+const LEVELS: usize = 6;
+
+/// Furthest deadline from the current time the wheel tells apart, about 2 years
+/// in milliseconds. Entries further than this wait on the top level, which is
+/// visited again every rotation.
+const MAX_DURATION: u64 = (1 << (SLOT_BITS * LEVELS)) - 1;
+
+/// Where an entry is kept on the wheel.
+#[derive(PartialEq, Eq, Copy, Clone)]
+enum Location {
+    Unscheduled,
+    Slot { level: usize, slot: usize },
+    Expired,
+}
+
+/// Links of an entry on the timer wheel, embedded within the entry.
+pub(super) struct TimerNode {
+    when: u64,
+    prev: Pointer,
+    next: Pointer,
+    location: Location,
+}
+
+impl Default for TimerNode {
+    fn default() -> Self {
+        TimerNode {
+            when: u64::MAX,
+            prev: Pointer::null(),
+            next: Pointer::null(),
+            location: Location::Unscheduled,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct List {
+    head: Pointer,
+    tail: Pointer,
+}
+
+impl List {
+    const EMPTY: List = List {
+        head: Pointer::Null,
+        tail: Pointer::Null,
+    };
+
+    fn is_empty(&self) -> bool {
+        self.head.is_null()
+    }
+
+    fn push_back(&mut self, nodes: &mut impl IndexMut<Pointer, Output = TimerNode>, ptr: Pointer) {
+        let node = &mut nodes[ptr];
+        node.prev = self.tail;
+        node.next = Pointer::null();
+        if self.tail.is_null() {
+            self.head = ptr;
+        } else {
+            nodes[self.tail].next = ptr;
+        }
+        self.tail = ptr;
+    }
+
+    fn unlink(&mut self, nodes: &mut impl IndexMut<Pointer, Output = TimerNode>, ptr: Pointer) {
+        let (prev, next) = {
+            let node = &mut nodes[ptr];
+            let links = (node.prev, node.next);
+            node.prev = Pointer::null();
+            node.next = Pointer::null();
+            links
+        };
+        if prev.is_null() {
+            self.head = next;
+        } else {
+            nodes[prev].next = next;
+        }
+        if next.is_null() {
+            self.tail = prev;
+        } else {
+            nodes[next].prev = prev;
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Level {
+    // bit per non-empty slot
+    occupied: u64,
+    slots: [List; SLOTS],
+}
+
+impl Level {
+    /// Returns the next non-empty slot, along with the time it starts, from given `now`.
+    fn next_expiration(&self, level: usize, now: u64) -> Option<(usize, u64)> {
+        if self.occupied == 0 {
+            return None;
+        }
+        let slot_range = slot_range(level);
+        let level_range = slot_range << SLOT_BITS;
+        // current slot is only occupied on top level, by entries of its next rotation,
+        // so that it's searched last
+        let after_now = ((now / slot_range) as usize + 1) % SLOTS;
+        let zeros = self
+            .occupied
+            .rotate_right(after_now as u32)
+            .trailing_zeros() as usize;
+        let slot = (after_now + zeros) % SLOTS;
+        let level_start = now & !(level_range - 1);
+        let mut deadline = level_start + slot as u64 * slot_range;
+        if deadline <= now {
+            // only on top level, whose slots are rotated around indefinitely
+            deadline += level_range;
+        }
+        Some((slot, deadline))
+    }
+}
+
+/// Returns the duration covered by a slot of given level.
+#[inline]
+fn slot_range(level: usize) -> u64 {
+    1 << (level * SLOT_BITS)
+}
+
+/// Returns the level an entry expiring at `when` is kept on, given current time `elapsed`,
+/// so that lower level always expires first.
+#[inline]
+fn level_for(elapsed: u64, when: u64) -> usize {
+    // the lowest bits are ignored to cap the leading zeros
+    let masked = ((elapsed ^ when) | (SLOTS as u64 - 1)).min(MAX_DURATION - 1);
+    let significant = 63 - masked.leading_zeros() as usize;
+    significant / SLOT_BITS
+}
+
+/// Hierarchical timing wheel of entries keyed by their expiry time, in milliseconds.
+///
+/// Each of its levels has 64 slots, covering 64 times the duration of the level below,
+/// from 1 millisecond slots of the lowest level. An entry is put on the level covering
+/// the distance to its expiry time, and moved down a level whenever its slot is reached,
+/// until it's expired. Scheduling and cancelling take constant time, and advancing the
+/// wheel takes amortized constant time per entry, regardless of how their time-to-live
+/// differ.
+pub(super) struct TimerWheel {
+    elapsed: u64,
+    levels: [Level; LEVELS],
+    expired: List,
+}
+
+impl TimerWheel {
+    pub(super) fn new() -> Self {
+        TimerWheel {
+            elapsed: 0,
+            levels: [Level {
+                occupied: 0,
+                slots: [List::EMPTY; SLOTS],
+            }; LEVELS],
+            expired: List::EMPTY,
+        }
+    }
+
+    /// Schedule the entry at pointer to expire at `when`, replacing its previous schedule.
+    /// Entry expiring at `u64::MAX` never expires, and is left unscheduled.
+    pub(super) fn schedule(
+        &mut self,
+        nodes: &mut impl IndexMut<Pointer, Output = TimerNode>,
+        ptr: Pointer,
+        when: u64,
+    ) {
+        let node = &nodes[ptr];
+        if node.when == when && node.location != Location::Unscheduled {
+            return;
+        }
+        self.cancel(nodes, ptr);
+        nodes[ptr].when = when;
+        if when != u64::MAX {
+            self.insert(nodes, ptr);
+        }
+    }
+
+    /// Take the entry at pointer off the wheel.
+    pub(super) fn cancel(
+        &mut self,
+        nodes: &mut impl IndexMut<Pointer, Output = TimerNode>,
+        ptr: Pointer,
+    ) {
+        match nodes[ptr].location {
+            Location::Unscheduled => {}
+            Location::Slot { level, slot } => {
+                let list = &mut self.levels[level].slots[slot];
+                list.unlink(nodes, ptr);
+                if list.is_empty() {
+                    self.levels[level].occupied &= !(1 << slot);
+                }
+            }
+            Location::Expired => self.expired.unlink(nodes, ptr),
+        }
+        let node = &mut nodes[ptr];
+        node.location = Location::Unscheduled;
+        node.when = u64::MAX;
+    }
+
+    /// Move the wheel forward to `now`, collecting every entry expiring
+    /// no later than `now`.
+    pub(super) fn advance(
+        &mut self,
+        nodes: &mut impl IndexMut<Pointer, Output = TimerNode>,
+        now: u64,
+    ) {
+        while let Some((level, slot, deadline)) = self.next_expiration() {
+            if deadline > now {
+                break;
+            }
+            let list = std::mem::replace(&mut self.levels[level].slots[slot], List::EMPTY);
+            self.levels[level].occupied &= !(1 << slot);
+            self.elapsed = self.elapsed.max(deadline);
+            let mut ptr = list.head;
+            while !ptr.is_null() {
+                let node = &mut nodes[ptr];
+                let next = node.next;
+                node.location = Location::Unscheduled;
+                self.insert(nodes, ptr);
+                ptr = next;
+            }
+        }
+        self.elapsed = self.elapsed.max(now);
+    }
+
+    /// Returns the entry which has expired the longest, if any.
+    pub(super) fn expired(&self) -> Option<Pointer> {
+        if self.expired.is_empty() {
+            None
+        } else {
+            Some(self.expired.head)
+        }
+    }
+
+    /// Returns the earliest time an entry on the wheel could expire, if any. Entry on upper
+    /// level is only known not to expire before its slot starts, when it's moved down.
+    #[cfg(feature = "asynchronous")]
+    pub(super) fn next_deadline(&self) -> Option<u64> {
+        if self.expired.is_empty() {
+            self.next_expiration().map(|(_, _, deadline)| deadline)
+        } else {
+            Some(self.elapsed)
+        }
+    }
+
+    /// Returns the first non-empty slot to reach, along with its level and its start.
+    fn next_expiration(&self) -> Option<(usize, usize, u64)> {
+        self.levels.iter().enumerate().find_map(|(level, slots)| {
+            slots
+                .next_expiration(level, self.elapsed)
+                .map(|(slot, deadline)| (level, slot, deadline))
+        })
+    }
+
+    /// Put unscheduled entry on the slot covering its expiry time, or on expired list.
+    fn insert(&mut self, nodes: &mut impl IndexMut<Pointer, Output = TimerNode>, ptr: Pointer) {
+        let when = nodes[ptr].when;
+        if when <= self.elapsed {
+            self.expired.push_back(nodes, ptr);
+            nodes[ptr].location = Location::Expired;
+        } else {
+            let level = level_for(self.elapsed, when);
+            let slot = (when >> (level * SLOT_BITS)) as usize % SLOTS;
+            self.levels[level].slots[slot].push_back(nodes, ptr);
+            self.levels[level].occupied |= 1 << slot;
+            nodes[ptr].location = Location::Slot { level, slot };
+        }
+    }
+}
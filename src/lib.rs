@@ -2,9 +2,20 @@
 //!
 //! Supported eviction strategy:
 //! - LRU (Least Recently Used, inspired by [LRU Cache](https://github.com/jeromefroe/lru-rs))
-//! - LFU (Least Frequently Used, TBD)
-//! - MRU (Most Recently Used, TBD)
-//! - FIFO (First In First Out, TBD)
+//! - LFU (Least Frequently Used)
+//! - MRU (Most Recently Used)
+//! - FIFO (First In First Out)
+//! - CLOCK (Second Chance)
+//! - S3-FIFO (Small, Main, and Ghost FIFO queues)
+//! - SLRU (Segmented LRU)
+//! - ARC (Adaptive Replacement Cache)
+//! - LIRS (Low Inter-reference Recency Set)
+//! - W-TinyLFU (LRU window with frequency based admission into segmented LRU)
+//! - GDSF (GreedyDual-Size-Frequency, weighing entries by their cost and size)
+//!
+//! Every strategy is an [`EvictionPolicy`](policy/trait.EvictionPolicy.html) plugged into
+//! the same [`Cache`](struct.Cache.html), e.g. `LfuCache<K, V>` is `Cache<K, V, Lfu>`.
+//! Custom strategy could be plugged the same way.
 //!
 //! Supporting async/.await powered by tokio runtime.
 //!
@@ -15,13 +26,16 @@
 //! If you do need to use reference for your value, on non-asynchronous do use `std::rc::Rc`,
 //! and on asynchronous do use `std::sync::Arc`
 //!
+//! Since async cache serves some reads under shared lock, its value has to be `Sync`
+//! as well as `Send`. This is a breaking change from 0.1, where `Send` is enough.
+//!
 //! ## Example basic
 //!
 //! ### Cargo.toml
 //!
 //! ```toml
 //! [dependencies]
-//! aba-cache = { version = "0.1", default-features = false }
+//! aba-cache = { version = "0.2", default-features = false }
 //! serde_json = { version = "1.0" }
 //! ```
 //!
@@ -58,7 +72,7 @@
 //! ### Cargo.toml
 //!
 //! ```toml
-//! aba-cache = { version = "0.1", default-features = false }
+//! aba-cache = { version = "0.2", default-features = false }
 //! serde_json = { version = "1.0" }
 //! tokio = { version = "0.2", features = ["macros", "rt-core"] }
 //! ```
@@ -95,8 +109,101 @@
 //!     Ok(())
 //! }
 //! ```
-mod lru;
+mod cache;
+pub mod policy;
+
+#[cfg(feature = "asynchronous")]
+pub use cache::asynchronous::Cache as AsyncCache;
+pub use cache::{
+    Builder, Cache, Expiry, ExpiryMode, Freshness, MockClock, MonotonicClock, Overweight,
+    TimeSource,
+};
+use policy::{Adaptive, Clock, Fifo, Gdsf, Lfu, Lirs, Lru, Mru, S3Fifo, Slru, TinyLfu};
+
+/// Cache with LRU eviction strategy
+pub type LruCache<K, V> = Cache<K, V, Lru>;
+/// Async version of Cache with LRU eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type LruAsyncCache<K, V> = AsyncCache<K, V, Lru>;
+/// Builder of Cache with LRU eviction strategy
+pub type LruCacheBuilder<K, V> = Builder<K, V, Lru>;
+
+/// Cache with LFU eviction strategy
+pub type LfuCache<K, V> = Cache<K, V, Lfu>;
+/// Async version of Cache with LFU eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type LfuAsyncCache<K, V> = AsyncCache<K, V, Lfu>;
+/// Builder of Cache with LFU eviction strategy
+pub type LfuCacheBuilder<K, V> = Builder<K, V, Lfu>;
+
+/// Cache with MRU eviction strategy
+pub type MruCache<K, V> = Cache<K, V, Mru>;
+/// Async version of Cache with MRU eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type MruAsyncCache<K, V> = AsyncCache<K, V, Mru>;
+/// Builder of Cache with MRU eviction strategy
+pub type MruCacheBuilder<K, V> = Builder<K, V, Mru>;
+
+/// Cache with FIFO eviction strategy
+pub type FifoCache<K, V> = Cache<K, V, Fifo>;
+/// Async version of Cache with FIFO eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type FifoAsyncCache<K, V> = AsyncCache<K, V, Fifo>;
+/// Builder of Cache with FIFO eviction strategy
+pub type FifoCacheBuilder<K, V> = Builder<K, V, Fifo>;
+
+/// Cache with CLOCK eviction strategy
+pub type ClockCache<K, V> = Cache<K, V, Clock>;
+/// Async version of Cache with CLOCK eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type ClockAsyncCache<K, V> = AsyncCache<K, V, Clock>;
+/// Builder of Cache with CLOCK eviction strategy
+pub type ClockCacheBuilder<K, V> = Builder<K, V, Clock>;
+
+/// Cache with S3-FIFO eviction strategy
+pub type S3FifoCache<K, V> = Cache<K, V, S3Fifo>;
+/// Async version of Cache with S3-FIFO eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type S3FifoAsyncCache<K, V> = AsyncCache<K, V, S3Fifo>;
+/// Builder of Cache with S3-FIFO eviction strategy
+pub type S3FifoCacheBuilder<K, V> = Builder<K, V, S3Fifo>;
+
+/// Cache with SLRU eviction strategy
+pub type SlruCache<K, V> = Cache<K, V, Slru>;
+/// Async version of Cache with SLRU eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type SlruAsyncCache<K, V> = AsyncCache<K, V, Slru>;
+/// Builder of Cache with SLRU eviction strategy
+pub type SlruCacheBuilder<K, V> = Builder<K, V, Slru>;
+
+/// Cache with ARC eviction strategy
+pub type ArcCache<K, V> = Cache<K, V, Adaptive>;
+/// Async version of Cache with ARC eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type ArcAsyncCache<K, V> = AsyncCache<K, V, Adaptive>;
+/// Builder of Cache with ARC eviction strategy
+pub type ArcCacheBuilder<K, V> = Builder<K, V, Adaptive>;
+
+/// Cache with LIRS eviction strategy
+pub type LirsCache<K, V> = Cache<K, V, Lirs>;
+/// Async version of Cache with LIRS eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type LirsAsyncCache<K, V> = AsyncCache<K, V, Lirs>;
+/// Builder of Cache with LIRS eviction strategy
+pub type LirsCacheBuilder<K, V> = Builder<K, V, Lirs>;
+
+/// Cache with W-TinyLFU eviction strategy
+pub type TinyLfuCache<K, V> = Cache<K, V, TinyLfu>;
+/// Async version of Cache with W-TinyLFU eviction strategy
+#[cfg(feature = "asynchronous")]
+pub type TinyLfuAsyncCache<K, V> = AsyncCache<K, V, TinyLfu>;
+/// Builder of Cache with W-TinyLFU eviction strategy
+pub type TinyLfuCacheBuilder<K, V> = Builder<K, V, TinyLfu>;
 
+/// Cache with GDSF eviction strategy
+pub type GdsfCache<K, V> = Cache<K, V, Gdsf>;
+/// Async version of Cache with GDSF eviction strategy
 #[cfg(feature = "asynchronous")]
-pub use lru::asynchronous::Cache as LruAsyncCache;
-pub use lru::Cache as LruCache;
+pub type GdsfAsyncCache<K, V> = AsyncCache<K, V, Gdsf>;
+/// Builder of Cache with GDSF eviction strategy
+pub type GdsfCacheBuilder<K, V> = Builder<K, V, Gdsf>;
@@ -0,0 +1,153 @@
+use super::{ghost::Ghosts, Entries, EvictionPolicy, Handle, Link, List};
+
+/// ARC (Adaptive Replacement Cache), balancing between recency and frequency.
+///
+/// Entries seen once are kept in `T1`, while entries seen at least twice
+/// are kept in `T2`. Keys recently evicted from each of them are remembered
+/// in ghost lists `B1` and `B2`, and a hit on those ghosts adapts the target
+/// size of `T1`, so that the policy tunes itself as the workload shifts.
+#[derive(Debug, Default)]
+pub struct Adaptive {
+    t1: List,
+    t2: List,
+    b1: Ghosts,
+    b2: Ghosts,
+
+    // target size of T1
+    target: usize,
+    capacity: usize,
+
+    // hash whose ghost hit is already adapted on choosing victim
+    adapted: Option<u64>,
+}
+
+/// Position of an entry within [`Adaptive`](struct.Adaptive.html) lists
+#[derive(Debug, Default)]
+pub struct AdaptiveMeta {
+    link: Link,
+    frequent: bool,
+    hash: u64,
+}
+
+impl AsMut<Link> for AdaptiveMeta {
+    fn as_mut(&mut self) -> &mut Link {
+        &mut self.link
+    }
+}
+
+impl Adaptive {
+    /// Adapt target size of T1 on ghost hit.
+    fn adapt(&mut self, hash: u64) {
+        if self.b1.contains(hash) {
+            let delta = (self.b2.len() / self.b1.len()).max(1);
+            self.target = (self.target + delta).min(self.capacity);
+        } else if self.b2.contains(hash) {
+            let delta = (self.b1.len() / self.b2.len()).max(1);
+            self.target = self.target.saturating_sub(delta);
+        }
+    }
+
+    /// Keep the ghosts within the capacity on insertion of new key.
+    fn trim(&mut self) {
+        if self.t1.len() + self.b1.len() >= self.capacity {
+            self.b1.pop_back();
+        } else if self.t1.len() + self.t2.len() + self.b1.len() + self.b2.len() >= 2 * self.capacity
+        {
+            self.b2.pop_back();
+        }
+    }
+
+    /// Evict from T1 or T2 depending on its target size, remembering
+    /// the evicted key on corresponding ghost list.
+    fn replace(&mut self, entries: &mut impl Entries<AdaptiveMeta>, hash: u64) -> Option<Handle> {
+        let t1_len = self.t1.len();
+        if t1_len > 0 && (t1_len > self.target || (t1_len == self.target && self.b2.contains(hash)))
+            || self.t2.is_empty()
+        {
+            let handle = self.t1.pop_back(entries)?;
+            self.b1.push_front(entries[handle].hash);
+            Some(handle)
+        } else {
+            let handle = self.t2.pop_back(entries)?;
+            self.b2.push_front(entries[handle].hash);
+            Some(handle)
+        }
+    }
+
+    /// Returns the size of T1, T2, B1, B2, and target size of T1.
+    #[cfg(test)]
+    pub(crate) fn sizes(&self) -> (usize, usize, usize, usize, usize) {
+        (
+            self.t1.len(),
+            self.t2.len(),
+            self.b1.len(),
+            self.b2.len(),
+            self.target,
+        )
+    }
+}
+
+impl EvictionPolicy for Adaptive {
+    type Meta = AdaptiveMeta;
+
+    fn on_init(&mut self, capacity: usize) {
+        self.capacity = capacity;
+    }
+
+    fn on_insert(&mut self, entries: &mut impl Entries<AdaptiveMeta>, handle: Handle, hash: u64) {
+        if self.adapted.take() != Some(hash) {
+            // no victim chosen for this key
+            if self.b1.contains(hash) || self.b2.contains(hash) {
+                self.adapt(hash);
+            } else {
+                self.trim();
+            }
+        }
+        entries[handle].hash = hash;
+        if self.b1.remove(hash) || self.b2.remove(hash) {
+            entries[handle].frequent = true;
+            self.t2.push_front(entries, handle);
+        } else {
+            self.t1.push_front(entries, handle);
+        }
+    }
+
+    fn on_access(&mut self, entries: &mut impl Entries<AdaptiveMeta>, handle: Handle) {
+        if entries[handle].frequent {
+            self.t2.move_to_front(entries, handle);
+        } else {
+            self.t1.unlink(entries, handle);
+            entries[handle].frequent = true;
+            self.t2.push_front(entries, handle);
+        }
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<AdaptiveMeta>, handle: Handle) {
+        if entries[handle].frequent {
+            self.t2.unlink(entries, handle);
+        } else {
+            self.t1.unlink(entries, handle);
+        }
+    }
+
+    fn victim(&mut self, entries: &mut impl Entries<AdaptiveMeta>, hash: u64) -> Option<Handle> {
+        self.adapted = Some(hash);
+        if self.b1.contains(hash) || self.b2.contains(hash) {
+            self.adapt(hash);
+            self.replace(entries, hash)
+        } else if self.t1.len() + self.b1.len() >= self.capacity {
+            if self.t1.len() < self.capacity {
+                self.b1.pop_back();
+                self.replace(entries, hash)
+            } else {
+                // T1 takes the whole cache, evict without remembering
+                self.t1.pop_back(entries)
+            }
+        } else {
+            if self.t1.len() + self.t2.len() + self.b1.len() + self.b2.len() >= 2 * self.capacity {
+                self.b2.pop_back();
+            }
+            self.replace(entries, hash)
+        }
+    }
+}
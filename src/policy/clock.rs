@@ -0,0 +1,60 @@
+use super::{Entries, EvictionPolicy, Handle, Link, List};
+use std::{
+    mem,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// CLOCK (second chance), approximating LRU without reordering entries on read.
+///
+/// Reading an entry only sets its reference bit, so that async cache serves
+/// hits under shared lock, unless entry expires after access. When choosing
+/// victim, the hand sweeps from the oldest entry, giving referenced entries
+/// a second chance by clearing their bit.
+#[derive(Debug, Default)]
+pub struct Clock(List);
+
+/// Position of an entry within [`Clock`](struct.Clock.html), along with its reference bit
+#[derive(Debug, Default)]
+pub struct ClockMeta {
+    link: Link,
+    referenced: AtomicBool,
+}
+
+impl AsMut<Link> for ClockMeta {
+    fn as_mut(&mut self) -> &mut Link {
+        &mut self.link
+    }
+}
+
+impl EvictionPolicy for Clock {
+    type Meta = ClockMeta;
+
+    const TRACK_ACCESS: bool = false;
+
+    fn on_insert(&mut self, entries: &mut impl Entries<ClockMeta>, handle: Handle, _: u64) {
+        self.0.push_front(entries, handle);
+    }
+
+    fn on_access(&mut self, entries: &mut impl Entries<ClockMeta>, handle: Handle) {
+        *entries[handle].referenced.get_mut() = true;
+    }
+
+    fn on_shared_access(&self, meta: &ClockMeta) {
+        meta.referenced.store(true, Ordering::Relaxed);
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<ClockMeta>, handle: Handle) {
+        self.0.unlink(entries, handle);
+    }
+
+    /// Sweep the hand until an entry without reference bit is found.
+    fn victim(&mut self, entries: &mut impl Entries<ClockMeta>, _: u64) -> Option<Handle> {
+        loop {
+            let handle = self.0.pop_back(entries)?;
+            if !mem::take(entries[handle].referenced.get_mut()) {
+                return Some(handle);
+            }
+            self.0.push_front(entries, handle);
+        }
+    }
+}
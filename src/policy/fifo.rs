@@ -0,0 +1,33 @@
+use super::{Entries, EvictionPolicy, Handle, Link, List};
+
+/// Evict the first inserted entry.
+///
+/// Reading doesn't reorder entries, so that async cache serves hits under shared lock,
+/// unless entry expires after access. Updating the value of an entry is treated as
+/// new insertion.
+#[derive(Debug, Default)]
+pub struct Fifo(List);
+
+impl EvictionPolicy for Fifo {
+    type Meta = Link;
+
+    const TRACK_ACCESS: bool = false;
+
+    fn on_insert(&mut self, entries: &mut impl Entries<Link>, handle: Handle, _: u64) {
+        self.0.push_front(entries, handle);
+    }
+
+    fn on_access(&mut self, _: &mut impl Entries<Link>, _: Handle) {}
+
+    fn on_update(&mut self, entries: &mut impl Entries<Link>, handle: Handle) {
+        self.0.move_to_front(entries, handle);
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<Link>, handle: Handle) {
+        self.0.unlink(entries, handle);
+    }
+
+    fn victim(&mut self, entries: &mut impl Entries<Link>, _: u64) -> Option<Handle> {
+        self.0.pop_back(entries)
+    }
+}
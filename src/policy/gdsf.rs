@@ -0,0 +1,84 @@
+use super::{Entries, EvictionPolicy, Handle, Weight};
+use std::collections::BTreeMap;
+
+/// GreedyDual-Size-Frequency, favoring small entries which are costly to fetch.
+///
+/// Each entry is given priority `clock + freq * cost / size`, using the
+/// [`Weight`](struct.Weight.html) given by cache's weigher. The entry with
+/// the lowest priority is chosen as victim, and the clock is raised to its
+/// priority, so that entries not accessed for a while age out. Entries
+/// with the same priority are evicted from the least recently prioritized.
+#[derive(Debug, Default)]
+pub struct Gdsf {
+    queue: BTreeMap<(u64, u64), Handle>,
+    clock: f64,
+    seq: u64,
+}
+
+/// Priority of an entry within [`Gdsf`](struct.Gdsf.html), along with its frequency and weight
+#[derive(Debug, Default)]
+pub struct GdsfMeta {
+    freq: u64,
+    weight: Weight,
+    // position on the queue, priority bits followed by sequence
+    key: Option<(u64, u64)>,
+}
+
+impl Gdsf {
+    /// Recompute priority of the entry, and move it on the queue.
+    fn prioritize(&mut self, entries: &mut impl Entries<GdsfMeta>, handle: Handle) {
+        let meta = &mut entries[handle];
+        if let Some(key) = meta.key {
+            self.queue.remove(&key);
+        }
+        let Weight { cost, size } = meta.weight;
+        let priority = self.clock + (meta.freq as f64) * (cost as f64) / (size.max(1) as f64);
+        // non-negative float keeps its order on its bits
+        let key = (priority.to_bits(), self.seq);
+        self.seq += 1;
+        meta.key = Some(key);
+        self.queue.insert(key, handle);
+    }
+
+    /// Returns the current clock, and priorities of entries from the lowest one.
+    #[cfg(test)]
+    pub(crate) fn priorities(&self) -> (f64, Vec<f64>) {
+        let priorities = self
+            .queue
+            .keys()
+            .map(|&(bits, _)| f64::from_bits(bits))
+            .collect();
+        (self.clock, priorities)
+    }
+}
+
+impl EvictionPolicy for Gdsf {
+    type Meta = GdsfMeta;
+
+    fn on_insert(&mut self, entries: &mut impl Entries<GdsfMeta>, handle: Handle, _: u64) {
+        entries[handle].freq = 1;
+        self.prioritize(entries, handle);
+    }
+
+    fn on_access(&mut self, entries: &mut impl Entries<GdsfMeta>, handle: Handle) {
+        entries[handle].freq += 1;
+        self.prioritize(entries, handle);
+    }
+
+    fn on_weigh(&mut self, entries: &mut impl Entries<GdsfMeta>, handle: Handle, weight: Weight) {
+        entries[handle].weight = weight;
+        self.prioritize(entries, handle);
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<GdsfMeta>, handle: Handle) {
+        if let Some(key) = entries[handle].key.take() {
+            self.queue.remove(&key);
+        }
+    }
+
+    fn victim(&mut self, _: &mut impl Entries<GdsfMeta>, _: u64) -> Option<Handle> {
+        let (&key, _) = self.queue.iter().next()?;
+        self.clock = f64::from_bits(key.0);
+        self.queue.remove(&key)
+    }
+}
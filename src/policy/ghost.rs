@@ -0,0 +1,80 @@
+use slab::Slab;
+use std::collections::HashMap;
+
+/// Hashes of keys no longer in the cache, ordered from the most recently
+/// inserted one. Used by policies which remember evicted entries.
+#[derive(Debug, Default)]
+pub(crate) struct Ghosts {
+    nodes: Slab<Node>,
+    index: HashMap<u64, usize>,
+
+    head: Option<usize>,
+    tail: Option<usize>,
+}
+
+#[derive(Debug)]
+struct Node {
+    hash: u64,
+
+    next: Option<usize>,
+    prev: Option<usize>,
+}
+
+impl Ghosts {
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub(crate) fn contains(&self, hash: u64) -> bool {
+        self.index.contains_key(&hash)
+    }
+
+    /// Returns the hash on the back.
+    pub(crate) fn back(&self) -> Option<u64> {
+        Some(self.nodes[self.tail?].hash)
+    }
+
+    /// Put the hash on the front, moving it there if already exists.
+    pub(crate) fn push_front(&mut self, hash: u64) {
+        self.remove(hash);
+        let id = self.nodes.insert(Node {
+            hash,
+            next: self.head,
+            prev: None,
+        });
+        if let Some(head) = self.head {
+            self.nodes[head].prev = Some(id);
+        } else {
+            self.tail = Some(id);
+        }
+        self.head = Some(id);
+        self.index.insert(hash, id);
+    }
+
+    /// Remove the hash, returns whether it exists.
+    pub(crate) fn remove(&mut self, hash: u64) -> bool {
+        if let Some(id) = self.index.remove(&hash) {
+            let Node { next, prev, .. } = self.nodes.remove(id);
+            if let Some(prev) = prev {
+                self.nodes[prev].next = next;
+            } else {
+                self.head = next;
+            }
+            if let Some(next) = next {
+                self.nodes[next].prev = prev;
+            } else {
+                self.tail = prev;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove and return the hash on the back.
+    pub(crate) fn pop_back(&mut self) -> Option<u64> {
+        let hash = self.nodes[self.tail?].hash;
+        self.remove(hash);
+        Some(hash)
+    }
+}
@@ -0,0 +1,145 @@
+use super::{Entries, EvictionPolicy, Handle, Link, List};
+use slab::Slab;
+
+/// Evict the least frequently used entry, with ties broken by the least
+/// recently used one.
+///
+/// Entries sharing the same access frequency are grouped into a bucket,
+/// and buckets are ordered by frequency, so that every operation is O(1).
+#[derive(Debug, Default)]
+pub struct Lfu {
+    buckets: Slab<Bucket>,
+
+    // bucket with the lowest frequency
+    lowest: Option<usize>,
+}
+
+/// Position of an entry within frequency bucket of [`Lfu`](struct.Lfu.html)
+#[derive(Debug, Default)]
+pub struct Frequency {
+    link: Link,
+    bucket: usize,
+}
+
+impl AsMut<Link> for Frequency {
+    fn as_mut(&mut self) -> &mut Link {
+        &mut self.link
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    freq: usize,
+    entries: List,
+
+    // bucket with higher frequency
+    next: Option<usize>,
+    // bucket with lower frequency
+    prev: Option<usize>,
+}
+
+impl Lfu {
+    /// Returns the bucket for newly inserted entry, allocating it if necessary.
+    fn first_bucket(&mut self) -> usize {
+        match self.lowest {
+            Some(lowest) if self.buckets[lowest].freq == 1 => lowest,
+            lowest => self.bucket_insert(1, None, lowest),
+        }
+    }
+
+    /// Allocate bucket with given frequency, between `prev` and `next`.
+    fn bucket_insert(&mut self, freq: usize, prev: Option<usize>, next: Option<usize>) -> usize {
+        let id = self.buckets.insert(Bucket {
+            freq,
+            entries: List::default(),
+            next,
+            prev,
+        });
+        if let Some(next) = next {
+            self.buckets[next].prev = Some(id);
+        }
+        if let Some(prev) = prev {
+            self.buckets[prev].next = Some(id);
+        } else {
+            self.lowest = Some(id);
+        }
+        id
+    }
+
+    /// Put the entry on the front of given bucket.
+    fn bucket_push(
+        &mut self,
+        entries: &mut impl Entries<Frequency>,
+        bucket: usize,
+        handle: Handle,
+    ) {
+        self.buckets[bucket].entries.push_front(entries, handle);
+        entries[handle].bucket = bucket;
+    }
+
+    /// Detach the entry from its bucket, deallocating the bucket
+    /// when it becomes empty.
+    fn bucket_unlink(&mut self, entries: &mut impl Entries<Frequency>, handle: Handle) {
+        let bucket = entries[handle].bucket;
+        self.buckets[bucket].entries.unlink(entries, handle);
+
+        if self.buckets[bucket].entries.is_empty() {
+            let Bucket { next, prev, .. } = self.buckets.remove(bucket);
+            if let Some(next) = next {
+                self.buckets[next].prev = prev;
+            }
+            if let Some(prev) = prev {
+                self.buckets[prev].next = next;
+            } else {
+                self.lowest = next;
+            }
+        }
+    }
+
+    /// Returns the number of entries of each frequency, from the lowest one.
+    #[cfg(test)]
+    pub(crate) fn frequencies(&self) -> Vec<(usize, usize)> {
+        let mut result = Vec::new();
+        let mut current = self.lowest;
+        while let Some(bucket) = current {
+            let bucket = &self.buckets[bucket];
+            result.push((bucket.freq, bucket.entries.len()));
+            current = bucket.next;
+        }
+        result
+    }
+}
+
+impl EvictionPolicy for Lfu {
+    type Meta = Frequency;
+
+    fn on_insert(&mut self, entries: &mut impl Entries<Frequency>, handle: Handle, _: u64) {
+        let bucket = self.first_bucket();
+        self.bucket_push(entries, bucket, handle);
+    }
+
+    /// Move the entry to the bucket with next frequency.
+    fn on_access(&mut self, entries: &mut impl Entries<Frequency>, handle: Handle) {
+        let current = entries[handle].bucket;
+        let (freq, next) = {
+            let bucket = &self.buckets[current];
+            (bucket.freq + 1, bucket.next)
+        };
+        let target = match next {
+            Some(next) if self.buckets[next].freq == freq => next,
+            _ => self.bucket_insert(freq, Some(current), next),
+        };
+        self.bucket_unlink(entries, handle);
+        self.bucket_push(entries, target, handle);
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<Frequency>, handle: Handle) {
+        self.bucket_unlink(entries, handle);
+    }
+
+    fn victim(&mut self, entries: &mut impl Entries<Frequency>, _: u64) -> Option<Handle> {
+        let handle = self.buckets[self.lowest?].entries.back()?;
+        self.bucket_unlink(entries, handle);
+        Some(handle)
+    }
+}
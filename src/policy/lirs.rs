@@ -0,0 +1,174 @@
+use super::{ghost::Ghosts, Entries, EvictionPolicy, Handle, Link, List};
+use std::collections::HashMap;
+
+/// LIRS (Low Inter-reference Recency Set), resisting loops slightly
+/// larger than the cache.
+///
+/// Entries with low inter-reference recency (LIR) take 99% of the capacity,
+/// and are never evicted directly. The rest holds resident entries with high
+/// inter-reference recency (HIR), queued in FIFO order where the victim is
+/// chosen from. Stack `S` orders recently seen keys, including evicted HIR
+/// keys, with a LIR entry always on its bottom. HIR key seen again while
+/// still in `S` becomes LIR, demoting the bottom LIR entry into HIR.
+#[derive(Debug, Default)]
+pub struct Lirs {
+    stack: Ghosts,
+    queue: List,
+    // evicted keys still in the stack, bounded by capacity
+    non_resident: Ghosts,
+    resident: HashMap<u64, Handle>,
+
+    lir_len: usize,
+    lir_cap: usize,
+    capacity: usize,
+}
+
+/// Position of an entry within [`Lirs`](struct.Lirs.html) HIR queue, along with its status
+#[derive(Debug, Default)]
+pub struct LirsMeta {
+    link: Link,
+    lir: bool,
+    hash: u64,
+}
+
+impl AsMut<Link> for LirsMeta {
+    fn as_mut(&mut self) -> &mut Link {
+        &mut self.link
+    }
+}
+
+impl Lirs {
+    /// Remove HIR keys from the bottom of the stack, until a LIR entry is found.
+    fn prune(&mut self, entries: &mut impl Entries<LirsMeta>) {
+        while let Some(hash) = self.stack.back() {
+            match self.resident.get(&hash) {
+                Some(&handle) if entries[handle].lir => break,
+                Some(_) => {}
+                None => {
+                    self.non_resident.remove(hash);
+                }
+            }
+            self.stack.remove(hash);
+        }
+    }
+
+    /// Demote LIR entries on the bottom of the stack into HIR queue,
+    /// until they fit in their share of the capacity.
+    fn demote(&mut self, entries: &mut impl Entries<LirsMeta>) {
+        while self.lir_len > self.lir_cap {
+            self.prune(entries);
+            let hash = match self.stack.back() {
+                Some(hash) => hash,
+                None => break,
+            };
+            let handle = match self.resident.get(&hash) {
+                Some(&handle) => handle,
+                None => break,
+            };
+            entries[handle].lir = false;
+            self.lir_len -= 1;
+            self.stack.remove(hash);
+            self.queue.push_front(entries, handle);
+        }
+        self.prune(entries);
+    }
+
+    /// Turn the entry into LIR on top of the stack.
+    fn promote(&mut self, entries: &mut impl Entries<LirsMeta>, handle: Handle) {
+        entries[handle].lir = true;
+        self.lir_len += 1;
+        self.stack.push_front(entries[handle].hash);
+    }
+
+    /// Forget the entry, leaving its key on the stack when `remember` is set.
+    fn forget(&mut self, entries: &mut impl Entries<LirsMeta>, handle: Handle, remember: bool) {
+        let hash = entries[handle].hash;
+        if self.resident.get(&hash) == Some(&handle) {
+            self.resident.remove(&hash);
+        }
+        if entries[handle].lir {
+            self.lir_len -= 1;
+            self.stack.remove(hash);
+        } else {
+            self.queue.unlink(entries, handle);
+            if !self.stack.contains(hash) {
+                return;
+            } else if remember {
+                self.non_resident.push_front(hash);
+                while self.non_resident.len() > self.capacity {
+                    if let Some(hash) = self.non_resident.pop_back() {
+                        self.stack.remove(hash);
+                    }
+                }
+                return;
+            }
+            self.stack.remove(hash);
+        }
+        self.prune(entries);
+    }
+
+    /// Returns the number of LIR entries, resident HIR entries, and keys on the stack.
+    #[cfg(test)]
+    pub(crate) fn sizes(&self) -> (usize, usize, usize) {
+        (self.lir_len, self.queue.len(), self.stack.len())
+    }
+}
+
+impl EvictionPolicy for Lirs {
+    type Meta = LirsMeta;
+
+    fn on_init(&mut self, capacity: usize) {
+        // at least one LIR entry, taking the whole capacity of 1
+        self.lir_cap = capacity.saturating_sub((capacity / 100).max(1)).max(1);
+        self.capacity = capacity;
+    }
+
+    fn on_insert(&mut self, entries: &mut impl Entries<LirsMeta>, handle: Handle, hash: u64) {
+        entries[handle].hash = hash;
+        self.resident.insert(hash, handle);
+        let seen = self.non_resident.remove(hash);
+        if self.lir_len < self.lir_cap {
+            self.promote(entries, handle);
+        } else if seen {
+            // seen again while still on the stack
+            self.promote(entries, handle);
+            self.demote(entries);
+        } else {
+            self.stack.push_front(hash);
+            self.queue.push_front(entries, handle);
+        }
+    }
+
+    fn on_access(&mut self, entries: &mut impl Entries<LirsMeta>, handle: Handle) {
+        let hash = entries[handle].hash;
+        if entries[handle].lir {
+            self.stack.push_front(hash);
+            self.prune(entries);
+        } else if self.stack.contains(hash) {
+            self.queue.unlink(entries, handle);
+            self.promote(entries, handle);
+            self.demote(entries);
+        } else {
+            self.stack.push_front(hash);
+            self.queue.move_to_front(entries, handle);
+        }
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<LirsMeta>, handle: Handle) {
+        self.forget(entries, handle, false);
+    }
+
+    /// Evict the oldest resident HIR entry, or the bottom LIR entry
+    /// when there's none.
+    fn victim(&mut self, entries: &mut impl Entries<LirsMeta>, _: u64) -> Option<Handle> {
+        let handle = match self.queue.back() {
+            Some(handle) => handle,
+            None => {
+                self.prune(entries);
+                *self.resident.get(&self.stack.back()?)?
+            }
+        };
+        self.forget(entries, handle, true);
+        Some(handle)
+    }
+}
@@ -0,0 +1,127 @@
+use super::{Entries, Handle};
+
+/// Position of an entry within a [`List`](struct.List.html)
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Link {
+    next: Option<Handle>,
+    prev: Option<Handle>,
+}
+
+impl Link {
+    /// Returns the entry after this one, towards the back of the list
+    pub fn next(&self) -> Option<Handle> {
+        self.next
+    }
+
+    /// Returns the entry before this one, towards the front of the list
+    pub fn prev(&self) -> Option<Handle> {
+        self.prev
+    }
+}
+
+impl AsMut<Link> for Link {
+    fn as_mut(&mut self) -> &mut Link {
+        self
+    }
+}
+
+/// Doubly linked list of entries, threaded through their `Meta`.
+/// Each `Link` should belong to at most one list at a time.
+#[derive(Debug, Default)]
+pub struct List {
+    head: Option<Handle>,
+    tail: Option<Handle>,
+    len: usize,
+}
+
+impl List {
+    /// Returns the entry on the front of the list
+    pub fn front(&self) -> Option<Handle> {
+        self.head
+    }
+
+    /// Returns the entry on the back of the list
+    pub fn back(&self) -> Option<Handle> {
+        self.tail
+    }
+
+    /// Returns the number of entries on the list
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns a bool indicating whether the list is empty or not
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Put the entry on the front of the list
+    pub fn push_front<M: AsMut<Link>>(&mut self, entries: &mut impl Entries<M>, handle: Handle) {
+        let head = self.head;
+        *entries[handle].as_mut() = Link {
+            next: head,
+            prev: None,
+        };
+        if let Some(head) = head {
+            entries[head].as_mut().prev = Some(handle);
+        } else {
+            self.tail = Some(handle);
+        }
+        self.head = Some(handle);
+        self.len += 1;
+    }
+
+    /// Put the entry on the back of the list
+    pub fn push_back<M: AsMut<Link>>(&mut self, entries: &mut impl Entries<M>, handle: Handle) {
+        let tail = self.tail;
+        *entries[handle].as_mut() = Link {
+            next: None,
+            prev: tail,
+        };
+        if let Some(tail) = tail {
+            entries[tail].as_mut().next = Some(handle);
+        } else {
+            self.head = Some(handle);
+        }
+        self.tail = Some(handle);
+        self.len += 1;
+    }
+
+    /// Detach the entry from the list
+    pub fn unlink<M: AsMut<Link>>(&mut self, entries: &mut impl Entries<M>, handle: Handle) {
+        let Link { next, prev } = std::mem::take(entries[handle].as_mut());
+        if let Some(prev) = prev {
+            entries[prev].as_mut().next = next;
+        } else {
+            self.head = next;
+        }
+        if let Some(next) = next {
+            entries[next].as_mut().prev = prev;
+        } else {
+            self.tail = prev;
+        }
+        self.len -= 1;
+    }
+
+    /// Move the entry, which is already on the list, to the front of the list
+    pub fn move_to_front<M: AsMut<Link>>(&mut self, entries: &mut impl Entries<M>, handle: Handle) {
+        if self.head != Some(handle) {
+            self.unlink(entries, handle);
+            self.push_front(entries, handle);
+        }
+    }
+
+    /// Detach and return the entry on the front of the list
+    pub fn pop_front<M: AsMut<Link>>(&mut self, entries: &mut impl Entries<M>) -> Option<Handle> {
+        let head = self.head?;
+        self.unlink(entries, head);
+        Some(head)
+    }
+
+    /// Detach and return the entry on the back of the list
+    pub fn pop_back<M: AsMut<Link>>(&mut self, entries: &mut impl Entries<M>) -> Option<Handle> {
+        let tail = self.tail?;
+        self.unlink(entries, tail);
+        Some(tail)
+    }
+}
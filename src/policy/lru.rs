@@ -0,0 +1,25 @@
+use super::{Entries, EvictionPolicy, Handle, Link, List};
+
+/// Evict the least recently used entry
+#[derive(Debug, Default)]
+pub struct Lru(List);
+
+impl EvictionPolicy for Lru {
+    type Meta = Link;
+
+    fn on_insert(&mut self, entries: &mut impl Entries<Link>, handle: Handle, _: u64) {
+        self.0.push_front(entries, handle);
+    }
+
+    fn on_access(&mut self, entries: &mut impl Entries<Link>, handle: Handle) {
+        self.0.move_to_front(entries, handle);
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<Link>, handle: Handle) {
+        self.0.unlink(entries, handle);
+    }
+
+    fn victim(&mut self, entries: &mut impl Entries<Link>, _: u64) -> Option<Handle> {
+        self.0.pop_back(entries)
+    }
+}
@@ -0,0 +1,174 @@
+//! Eviction policies, deciding which entry is replaced when the cache is full.
+//!
+//! Custom policy can be plugged into [`Cache`](../struct.Cache.html) by implementing
+//! [`EvictionPolicy`](trait.EvictionPolicy.html). Each entry of the cache carries
+//! policy's `Meta`, which could be used to link entries together using [`List`](struct.List.html).
+//!
+//! # Example
+//!
+//! ```
+//! use aba_cache as cache;
+//! use cache::{
+//!     policy::{Entries, EvictionPolicy, Handle, Link, List},
+//!     Cache,
+//! };
+//!
+//! /// Evict the newest entry, ignoring access
+//! #[derive(Default)]
+//! struct Lifo(List);
+//!
+//! impl EvictionPolicy for Lifo {
+//!     type Meta = Link;
+//!
+//!     fn on_insert(&mut self, entries: &mut impl Entries<Link>, handle: Handle, _: u64) {
+//!         self.0.push_front(entries, handle);
+//!     }
+//!
+//!     fn on_access(&mut self, _: &mut impl Entries<Link>, _: Handle) {}
+//!
+//!     fn on_remove(&mut self, entries: &mut impl Entries<Link>, handle: Handle) {
+//!         self.0.unlink(entries, handle);
+//!     }
+//!
+//!     fn victim(&mut self, entries: &mut impl Entries<Link>, _: u64) -> Option<Handle> {
+//!         self.0.pop_front(entries)
+//!     }
+//! }
+//!
+//! let mut cache = Cache::<_, _, Lifo>::builder()
+//!     .max_capacity(2)
+//!     .timeout_secs(60)
+//!     .build();
+//!
+//! cache.put(1, "a");
+//! cache.put(2, "b");
+//! cache.get(&1);
+//!
+//! assert_eq!(cache.push(3, "c"), Some((2, "b")));
+//! ```
+use std::ops::IndexMut;
+
+pub use adaptive::{Adaptive, AdaptiveMeta};
+pub use clock::{Clock, ClockMeta};
+pub use fifo::Fifo;
+pub use gdsf::{Gdsf, GdsfMeta};
+pub use lfu::{Frequency, Lfu};
+pub use lirs::{Lirs, LirsMeta};
+pub use list::{Link, List};
+pub use lru::Lru;
+pub use mru::Mru;
+pub use s3_fifo::{S3Fifo, S3FifoMeta};
+pub use slru::{Slru, SlruMeta};
+pub use tiny_lfu::{TinyLfu, TinyLfuMeta};
+
+mod adaptive;
+mod clock;
+mod fifo;
+mod gdsf;
+mod ghost;
+mod lfu;
+mod lirs;
+mod list;
+mod lru;
+mod mru;
+mod s3_fifo;
+mod sketch;
+mod slru;
+mod tiny_lfu;
+
+#[cfg(test)]
+mod tests;
+
+/// Opaque identifier of an entry within the cache.
+/// It stays valid until the entry is removed or chosen as victim.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Handle {
+    pub(crate) slab: usize,
+    pub(crate) pos: usize,
+}
+
+/// Access to policy's `Meta` of every entry in the cache.
+pub trait Entries<M>: IndexMut<Handle, Output = M> {}
+
+impl<M, T: IndexMut<Handle, Output = M> + ?Sized> Entries<M> for T {}
+
+/// Cost of fetching an entry and the size it takes, used by size aware policy
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Weight {
+    /// Cost of fetching the entry again once it's evicted, weighed by GDSF policy, unlike
+    /// the compute time given to `put_with_compute_time` for refresh hints
+    pub cost: u64,
+    /// Size taken by the entry
+    pub size: usize,
+}
+
+impl Default for Weight {
+    /// Unit cost and size, used when the cache has no weigher
+    fn default() -> Self {
+        Weight { cost: 1, size: 1 }
+    }
+}
+
+/// Compute the [`Weight`](struct.Weight.html) of an entry on insertion or update.
+/// Implemented by any `Fn(&K, &V) -> Weight` closure.
+pub trait Weigher<K, V> {
+    /// Returns the weight of given entry
+    fn weigh(&self, key: &K, value: &V) -> Weight;
+}
+
+impl<K, V, F: Fn(&K, &V) -> Weight> Weigher<K, V> for F {
+    fn weigh(&self, key: &K, value: &V) -> Weight {
+        self(key, value)
+    }
+}
+
+/// Strategy deciding which entry is evicted when the cache is full.
+///
+/// Note that expired entry is removed regardless of policy, in such case
+/// `on_remove` is called.
+pub trait EvictionPolicy {
+    /// Per entry state, reset to its default on insertion
+    type Meta: Default;
+
+    /// Whether reading an entry counts as access. When `false`, reading calls
+    /// `on_shared_access` instead of `on_access`, so that async cache only needs
+    /// shared lock to read, unless the cache expires entry after it's last read.
+    const TRACK_ACCESS: bool = true;
+
+    /// Called once on cache creation with the number of entries the cache
+    /// holds before it evicts, or grows.
+    fn on_init(&mut self, _capacity: usize) {}
+
+    /// Called when new entry is inserted, `hash` is the hash of its key.
+    fn on_insert(&mut self, entries: &mut impl Entries<Self::Meta>, handle: Handle, hash: u64);
+
+    /// Called when existing entry is read.
+    fn on_access(&mut self, entries: &mut impl Entries<Self::Meta>, handle: Handle);
+
+    /// Called when existing entry is read while `TRACK_ACCESS` is `false`,
+    /// with only shared access to the entry's `Meta`.
+    fn on_shared_access(&self, _meta: &Self::Meta) {}
+
+    /// Called when value of existing entry is replaced, treated as access by default.
+    fn on_update(&mut self, entries: &mut impl Entries<Self::Meta>, handle: Handle) {
+        self.on_access(entries, handle)
+    }
+
+    /// Called after `on_insert` and `on_update` with the weight of the entry,
+    /// given by cache's weigher.
+    fn on_weigh(
+        &mut self,
+        _entries: &mut impl Entries<Self::Meta>,
+        _handle: Handle,
+        _weight: Weight,
+    ) {
+    }
+
+    /// Called when entry is removed from the cache without being chosen
+    /// as victim, e.g. because it expires.
+    fn on_remove(&mut self, entries: &mut impl Entries<Self::Meta>, handle: Handle);
+
+    /// Choose and detach the entry to be replaced by new entry, whose key
+    /// has given `hash`. Returning `None` let the cache grow instead.
+    fn victim(&mut self, entries: &mut impl Entries<Self::Meta>, hash: u64) -> Option<Handle>;
+}
@@ -0,0 +1,153 @@
+use super::{ghost::Ghosts, Entries, EvictionPolicy, Handle, Link, List};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+const MAX_FREQ: u8 = 3;
+
+/// S3-FIFO, made of small FIFO, main FIFO, and ghost queue.
+///
+/// New entries are inserted into small FIFO, taking 10% of the capacity.
+/// Entry leaving small FIFO is moved into main FIFO when it's read again
+/// while in small FIFO, otherwise it's evicted and its key is remembered
+/// in ghost queue, so that it's inserted directly into main FIFO when it
+/// comes back. Entry leaving main FIFO is reinserted while it's read since
+/// its last reinsertion.
+///
+/// Reading an entry only increments its small frequency counter, so that
+/// async cache serves hits under shared lock, unless entry expires after access.
+#[derive(Debug, Default)]
+pub struct S3Fifo {
+    small: List,
+    main: List,
+    ghost: Ghosts,
+
+    small_cap: usize,
+    main_cap: usize,
+}
+
+/// Position of an entry within [`S3Fifo`](struct.S3Fifo.html) queues, along with its frequency
+#[derive(Debug, Default)]
+pub struct S3FifoMeta {
+    link: Link,
+    main: bool,
+    freq: AtomicU8,
+    hash: u64,
+}
+
+impl AsMut<Link> for S3FifoMeta {
+    fn as_mut(&mut self) -> &mut Link {
+        &mut self.link
+    }
+}
+
+impl S3Fifo {
+    /// Evict the oldest entry of small FIFO, or move it into main FIFO
+    /// when it's read again. Returns `None` if the entry is moved.
+    fn evict_small(
+        &mut self,
+        entries: &mut impl Entries<S3FifoMeta>,
+        handle: Handle,
+    ) -> Option<Handle> {
+        self.small.unlink(entries, handle);
+        let meta = &mut entries[handle];
+        if *meta.freq.get_mut() > 0 {
+            *meta.freq.get_mut() = 0;
+            meta.main = true;
+            self.main.push_front(entries, handle);
+            None
+        } else {
+            let hash = meta.hash;
+            self.ghost.push_front(hash);
+            while self.ghost.len() > self.main_cap.max(1) {
+                self.ghost.pop_back();
+            }
+            Some(handle)
+        }
+    }
+
+    /// Evict the oldest entry of main FIFO, or reinsert it when it's read
+    /// since its last reinsertion. Returns `None` if the entry is reinserted.
+    fn evict_main(
+        &mut self,
+        entries: &mut impl Entries<S3FifoMeta>,
+        handle: Handle,
+    ) -> Option<Handle> {
+        self.main.unlink(entries, handle);
+        let freq = entries[handle].freq.get_mut();
+        if *freq > 0 {
+            *freq -= 1;
+            self.main.push_front(entries, handle);
+            None
+        } else {
+            Some(handle)
+        }
+    }
+
+    /// Returns the number of entries of small and main FIFO, and ghost queue.
+    #[cfg(test)]
+    pub(crate) fn sizes(&self) -> (usize, usize, usize) {
+        (self.small.len(), self.main.len(), self.ghost.len())
+    }
+}
+
+impl EvictionPolicy for S3Fifo {
+    type Meta = S3FifoMeta;
+
+    const TRACK_ACCESS: bool = false;
+
+    fn on_init(&mut self, capacity: usize) {
+        self.small_cap = (capacity / 10).max(1);
+        self.main_cap = capacity.saturating_sub(self.small_cap);
+    }
+
+    fn on_insert(&mut self, entries: &mut impl Entries<S3FifoMeta>, handle: Handle, hash: u64) {
+        entries[handle].hash = hash;
+        if self.ghost.remove(hash) {
+            entries[handle].main = true;
+            self.main.push_front(entries, handle);
+        } else {
+            self.small.push_front(entries, handle);
+        }
+    }
+
+    fn on_access(&mut self, entries: &mut impl Entries<S3FifoMeta>, handle: Handle) {
+        let freq = entries[handle].freq.get_mut();
+        *freq = (*freq + 1).min(MAX_FREQ);
+    }
+
+    fn on_shared_access(&self, meta: &S3FifoMeta) {
+        let _ = meta
+            .freq
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |freq| {
+                if freq < MAX_FREQ {
+                    Some(freq + 1)
+                } else {
+                    None
+                }
+            });
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<S3FifoMeta>, handle: Handle) {
+        if entries[handle].main {
+            self.main.unlink(entries, handle);
+        } else {
+            self.small.unlink(entries, handle);
+        }
+    }
+
+    fn victim(&mut self, entries: &mut impl Entries<S3FifoMeta>, _: u64) -> Option<Handle> {
+        loop {
+            let evicted = match self.small.back() {
+                Some(handle) if self.small.len() >= self.small_cap || self.main.is_empty() => {
+                    self.evict_small(entries, handle)
+                }
+                _ => {
+                    let handle = self.main.back()?;
+                    self.evict_main(entries, handle)
+                }
+            };
+            if evicted.is_some() {
+                return evicted;
+            }
+        }
+    }
+}
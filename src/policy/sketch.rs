@@ -0,0 +1,80 @@
+/// Count-min sketch estimating how often a key hash is seen, with 4 rows
+/// of small saturating counters.
+///
+/// Every counter is halved once the number of recorded increments reaches
+/// the sample size, so that estimation follows recent history.
+#[derive(Debug)]
+pub(crate) struct CountMinSketch {
+    table: Vec<u8>,
+    bits: u32,
+
+    additions: usize,
+    sample: usize,
+}
+
+const DEPTH: usize = 4;
+const MAX_COUNT: u8 = 15;
+const SEEDS: [u64; DEPTH] = [
+    0x9E37_79B9_7F4A_7C15,
+    0xC2B2_AE3D_27D4_EB4F,
+    0x1656_67B1_9E37_79F9,
+    0xFF51_AFD7_ED55_8CCD,
+];
+
+impl CountMinSketch {
+    /// Create sketch for cache holding `capacity` entries.
+    pub(crate) fn new(capacity: usize) -> Self {
+        let width = (capacity.max(16) * 4).next_power_of_two();
+        CountMinSketch {
+            table: vec![0; width * DEPTH],
+            bits: width.trailing_zeros(),
+            additions: 0,
+            sample: capacity.max(1) * 10,
+        }
+    }
+
+    /// Returns the estimated frequency of given hash.
+    pub(crate) fn frequency(&self, hash: u64) -> u8 {
+        (0..DEPTH)
+            .map(|row| self.table[self.index(row, hash)])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Record an occurrence of given hash.
+    pub(crate) fn increment(&mut self, hash: u64) {
+        let mut added = false;
+        for row in 0..DEPTH {
+            let index = self.index(row, hash);
+            if self.table[index] < MAX_COUNT {
+                self.table[index] += 1;
+                added = true;
+            }
+        }
+        if added {
+            self.additions += 1;
+            if self.additions >= self.sample {
+                self.age();
+            }
+        }
+    }
+
+    /// Halve every counter.
+    fn age(&mut self) {
+        for count in self.table.iter_mut() {
+            *count >>= 1;
+        }
+        self.additions /= 2;
+    }
+
+    fn index(&self, row: usize, hash: u64) -> usize {
+        let column = hash.wrapping_mul(SEEDS[row]) >> (64 - self.bits);
+        (row << self.bits) | column as usize
+    }
+}
+
+impl Default for CountMinSketch {
+    fn default() -> Self {
+        CountMinSketch::new(0)
+    }
+}
@@ -0,0 +1,108 @@
+use super::{Entries, EvictionPolicy, Handle, Link, List};
+
+/// Segmented LRU, keeping the working set from being evicted by a scan.
+///
+/// New entries are inserted into probationary segment, and promoted into
+/// protected segment on their second hit. When protected segment is full,
+/// its least recently used entry is demoted back into probationary segment,
+/// where the victim is chosen from.
+#[derive(Debug)]
+pub struct Slru {
+    probation: List,
+    protected: List,
+
+    protected_percent: usize,
+    protected_cap: usize,
+}
+
+/// Position of an entry within [`Slru`](struct.Slru.html) segments
+#[derive(Debug, Default)]
+pub struct SlruMeta {
+    link: Link,
+    protected: bool,
+}
+
+impl AsMut<Link> for SlruMeta {
+    fn as_mut(&mut self) -> &mut Link {
+        &mut self.link
+    }
+}
+
+impl Default for Slru {
+    /// Protected segment takes 80% of the capacity
+    fn default() -> Self {
+        Slru::with_protected_percent(80)
+    }
+}
+
+impl Slru {
+    /// Create policy whose protected segment takes `percent` of the capacity,
+    /// the rest is taken by probationary segment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percent` is greater than 100
+    pub fn with_protected_percent(percent: usize) -> Self {
+        if percent > 100 {
+            panic!("Protected segment defined with more than 100 percent")
+        }
+        Slru {
+            probation: List::default(),
+            protected: List::default(),
+            protected_percent: percent,
+            protected_cap: 0,
+        }
+    }
+
+    /// Returns the number of entries of probationary and protected segments.
+    #[cfg(test)]
+    pub(crate) fn sizes(&self) -> (usize, usize) {
+        (self.probation.len(), self.protected.len())
+    }
+}
+
+impl EvictionPolicy for Slru {
+    type Meta = SlruMeta;
+
+    fn on_init(&mut self, capacity: usize) {
+        self.protected_cap = capacity * self.protected_percent / 100;
+    }
+
+    fn on_insert(&mut self, entries: &mut impl Entries<SlruMeta>, handle: Handle, _: u64) {
+        self.probation.push_front(entries, handle);
+    }
+
+    /// Promote the entry into protected segment, demoting the overflow
+    /// back into probationary segment.
+    fn on_access(&mut self, entries: &mut impl Entries<SlruMeta>, handle: Handle) {
+        if entries[handle].protected {
+            self.protected.move_to_front(entries, handle);
+        } else if self.protected_cap > 0 {
+            self.probation.unlink(entries, handle);
+            self.protected.push_front(entries, handle);
+            entries[handle].protected = true;
+            if self.protected.len() > self.protected_cap {
+                if let Some(last) = self.protected.pop_back(entries) {
+                    self.probation.push_front(entries, last);
+                    entries[last].protected = false;
+                }
+            }
+        } else {
+            self.probation.move_to_front(entries, handle);
+        }
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<SlruMeta>, handle: Handle) {
+        if entries[handle].protected {
+            self.protected.unlink(entries, handle);
+        } else {
+            self.probation.unlink(entries, handle);
+        }
+    }
+
+    fn victim(&mut self, entries: &mut impl Entries<SlruMeta>, _: u64) -> Option<Handle> {
+        self.probation
+            .pop_back(entries)
+            .or_else(|| self.protected.pop_back(entries))
+    }
+}
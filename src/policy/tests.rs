@@ -0,0 +1,1009 @@
+use super::{
+    sketch::CountMinSketch, Adaptive, Clock, EvictionPolicy, Fifo, Gdsf, Lfu, Lirs, Lru, Mru,
+    S3Fifo, Slru, TinyLfu, Weight,
+};
+#[cfg(feature = "asynchronous")]
+use crate::AsyncCache;
+use crate::{
+    ArcCache, Cache, ClockCache, ExpiryMode, FifoCache, GdsfCache, LfuCache, LirsCache, LruCache,
+    MockClock, MruCache, S3FifoCache, SlruCache, TinyLfuCache,
+};
+use std::time::Duration;
+
+#[test]
+fn test_lfu_get_increment_frequency() {
+    let mut cache = LfuCache::<usize, &str>::new(3, 60);
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    assert_eq!(cache.policy().frequencies(), vec![(1, 3)]);
+
+    assert_eq!(cache.get(&2), Some(&"two"));
+    assert_eq!(cache.policy().frequencies(), vec![(1, 2), (2, 1)]);
+
+    assert_eq!(cache.get(&2), Some(&"two"));
+    assert_eq!(cache.get(&3), Some(&"three"));
+    assert_eq!(cache.policy().frequencies(), vec![(1, 1), (2, 1), (3, 1)]);
+
+    assert_eq!(cache.put(1, "uno"), Some("one"));
+    assert_eq!(cache.policy().frequencies(), vec![(2, 2), (3, 1)]);
+}
+
+#[test]
+fn test_lfu_bounded_evict_least_frequently_used() {
+    let mut cache = LfuCache::<usize, &str>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    cache.get(&1);
+    cache.get(&1);
+    cache.get(&3);
+
+    assert_eq!(cache.push(4, "four"), Some((2, "two")));
+    assert_eq!(cache.policy().frequencies(), vec![(1, 1), (2, 1), (3, 1)]);
+    assert_eq!(cache.push(5, "five"), Some((4, "four")));
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.capacity(), 3);
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&3), Some(&"three"));
+    assert_eq!(cache.get(&5), Some(&"five"));
+}
+
+#[test]
+fn test_lfu_bounded_evict_least_recently_used_on_same_frequency() {
+    let mut cache = LfuCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.get(&2);
+    cache.get(&1);
+
+    assert_eq!(cache.push(3, "three"), Some((2, "two")));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&3), Some(&"three"));
+}
+
+#[test]
+fn test_lfu_reuse_expire_entry() {
+    let clock = MockClock::new();
+    let mut cache = LfuCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.get(&1);
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.push(2, "two"), Some((1, "one")));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.policy().frequencies(), vec![(1, 1)]);
+}
+
+#[test]
+fn test_lfu_evict_expire_entry() {
+    let clock = MockClock::new();
+    let mut cache = LfuCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    cache.get(&2);
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.capacity(), 4);
+
+    clock.advance(Duration::from_secs(1));
+    cache.evict();
+    assert_eq!(cache.len(), 0);
+    assert_eq!(cache.capacity(), 0);
+    assert!(cache.policy().frequencies().is_empty());
+
+    cache.put(4, "four");
+    assert_eq!(cache.get(&4), Some(&"four"));
+    assert_eq!(cache.policy().frequencies(), vec![(2, 1)]);
+}
+
+#[test]
+fn test_mru_bounded_evict_most_recently_used() {
+    let mut cache = MruCache::<usize, &str>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    assert_eq!(cache.get(&1), Some(&"one"));
+
+    assert_eq!(cache.push(4, "four"), Some((1, "one")));
+    assert_eq!(cache.push(5, "five"), Some((4, "four")));
+    assert_eq!(cache.get(&2), Some(&"two"));
+    assert_eq!(cache.push(6, "six"), Some((2, "two")));
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.capacity(), 3);
+}
+
+#[test]
+fn test_mru_bounded_cyclic_scan() {
+    let mut cache = MruCache::<usize, usize>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .build();
+    let mut lru = LruCache::<usize, usize>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .build();
+
+    let (mut hits, mut lru_hits) = (0, 0);
+    for _ in 0..3 {
+        for key in 0..4 {
+            if cache.get(&key).is_some() {
+                hits += 1;
+            } else {
+                cache.put(key, key);
+            }
+            if lru.get(&key).is_some() {
+                lru_hits += 1;
+            } else {
+                lru.put(key, key);
+            }
+        }
+    }
+    assert_eq!(lru_hits, 0);
+    assert!(hits > 0);
+}
+
+#[test]
+fn test_mru_bounded_reuse_expire_entry() {
+    let clock = MockClock::new();
+    let mut cache = MruCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(1)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.push(3, "three"), Some((1, "one")));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn test_fifo_bounded_evict_first_inserted() {
+    let mut cache = FifoCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    assert_eq!(cache.get(&1), Some(&"one"));
+
+    assert_eq!(cache.push(3, "three"), Some((1, "one")));
+    assert_eq!(cache.push(2, "deux"), Some((2, "two")));
+    assert_eq!(cache.push(4, "four"), Some((3, "three")));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.capacity(), 2);
+    assert_eq!(cache.get(&2), Some(&"deux"));
+    assert_eq!(cache.get(&4), Some(&"four"));
+}
+
+#[test]
+fn test_fifo_get_expire_entry() {
+    let clock = MockClock::new();
+    let mut cache = FifoCache::<usize, &str>::builder()
+        .multiply_cap(2)
+        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(1)))
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    assert_eq!(cache.get(&2), Some(&"two"));
+
+    // shared read leaves expired entry to be evicted
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.len(), 3);
+
+    cache.evict();
+    assert_eq!(cache.len(), 0);
+    assert_eq!(cache.capacity(), 0);
+}
+
+#[test]
+fn test_fifo_get_refresh_expire_after_access() {
+    let clock = MockClock::new();
+    let mut cache = FifoCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(10)
+        .clock(clock.clone())
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    clock.advance(Duration::from_secs(6));
+    assert_eq!(cache.get(&1), Some(&"one"));
+
+    clock.advance(Duration::from_secs(6));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), None);
+
+    // reading doesn't reorder entries
+    cache.put(2, "two");
+    assert_eq!(cache.push(3, "three"), Some((1, "one")));
+}
+
+#[test]
+fn test_arc_get_promote_to_frequent() {
+    let mut cache = ArcCache::<usize, &str>::new(3, 60);
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    assert_eq!(cache.policy().sizes(), (3, 0, 0, 0, 0));
+
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.policy().sizes(), (2, 1, 0, 0, 0));
+
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.policy().sizes(), (2, 1, 0, 0, 0));
+}
+
+#[test]
+fn test_arc_bounded_ghost_hit_adapt_target() {
+    let mut cache = ArcCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    assert_eq!(cache.get(&1), Some(&"one"));
+
+    // recent entry evicted, remembered on B1
+    assert_eq!(cache.push(3, "three"), Some((2, "two")));
+    assert_eq!(cache.policy().sizes(), (1, 1, 1, 0, 0));
+
+    // hit on B1 grows target of T1, evicting frequent entry to B2
+    assert_eq!(cache.push(2, "deux"), Some((1, "one")));
+    assert_eq!(cache.policy().sizes(), (1, 1, 0, 1, 1));
+
+    // hit on B2 shrinks target of T1
+    assert_eq!(cache.push(1, "un"), Some((3, "three")));
+    assert_eq!(cache.policy().sizes(), (0, 2, 1, 0, 0));
+    assert_eq!(cache.get(&1), Some(&"un"));
+    assert_eq!(cache.get(&2), Some(&"deux"));
+}
+
+#[test]
+fn test_arc_bounded_scan_resistant() {
+    let mut cache = ArcCache::<usize, usize>::builder()
+        .max_capacity(4)
+        .timeout_secs(60)
+        .build();
+    let mut lru = LruCache::<usize, usize>::builder()
+        .max_capacity(4)
+        .timeout_secs(60)
+        .build();
+
+    // hot keys 0 and 1 accessed twice, then interleaved with one-time scan
+    for key in (0..2).chain(0..2) {
+        if cache.get(&key).is_none() {
+            cache.put(key, key);
+        }
+        if lru.get(&key).is_none() {
+            lru.put(key, key);
+        }
+    }
+    let (mut hits, mut lru_hits) = (0, 0);
+    for round in 0..10 {
+        let scan = 100 + round * 4;
+        for key in (scan..scan + 4).chain(0..2) {
+            if cache.get(&key).is_some() {
+                hits += 1;
+            } else {
+                cache.put(key, key);
+            }
+            if lru.get(&key).is_some() {
+                lru_hits += 1;
+            } else {
+                lru.put(key, key);
+            }
+        }
+    }
+    assert_eq!(lru_hits, 0);
+    assert_eq!(hits, 20);
+}
+
+#[test]
+fn test_sketch_estimate_and_age() {
+    let mut sketch = CountMinSketch::new(1);
+
+    for _ in 0..4 {
+        sketch.increment(1);
+    }
+    sketch.increment(2);
+    assert_eq!(sketch.frequency(1), 4);
+    assert_eq!(sketch.frequency(2), 1);
+    assert_eq!(sketch.frequency(3), 0);
+
+    // reaching sample size of 10 halves every counter
+    for _ in 0..5 {
+        sketch.increment(3);
+    }
+    assert_eq!(sketch.frequency(1), 2);
+    assert_eq!(sketch.frequency(2), 0);
+    assert_eq!(sketch.frequency(3), 2);
+}
+
+#[test]
+fn test_tiny_lfu_bounded_admission() {
+    let mut cache = TinyLfuCache::<usize, &str>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    assert_eq!(cache.policy().sizes(), (1, 2, 0));
+
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.policy().sizes(), (1, 1, 1));
+
+    // entry leaving window is not used more than main's victim
+    assert_eq!(cache.push(4, "four"), Some((3, "three")));
+
+    // entry leaving window is used more than main's victim
+    assert_eq!(cache.get(&4), Some(&"four"));
+    assert_eq!(cache.get(&4), Some(&"four"));
+    assert_eq!(cache.push(5, "five"), Some((2, "two")));
+    assert_eq!(cache.policy().sizes(), (1, 1, 1));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&4), Some(&"four"));
+    assert_eq!(cache.get(&5), Some(&"five"));
+}
+
+#[test]
+fn test_tiny_lfu_bounded_admit_remembered_frequency() {
+    let mut cache = TinyLfuCache::<usize, &str>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    assert_eq!(cache.push(4, "four"), Some((3, "three")));
+
+    // rejected key is still counted by the sketch after it's gone
+    assert_eq!(cache.push(3, "trois"), Some((4, "four")));
+    assert_eq!(cache.push(5, "five"), Some((1, "one")));
+    assert_eq!(cache.policy().sizes(), (1, 2, 0));
+    assert_eq!(cache.get(&3), Some(&"trois"));
+    assert_eq!(cache.get(&5), Some(&"five"));
+}
+
+#[test]
+fn test_tiny_lfu_bounded_one_hit_wonder() {
+    let mut cache = TinyLfuCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+    let mut lru = LruCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+
+    // hot keys accessed several times, then interleaved with one-time scan
+    for _ in 0..3 {
+        for key in 0..5 {
+            if cache.get(&key).is_none() {
+                cache.put(key, key);
+            }
+            if lru.get(&key).is_none() {
+                lru.put(key, key);
+            }
+        }
+    }
+    let (mut hits, mut lru_hits) = (0, 0);
+    for round in 0..10 {
+        let scan = 100 + round * 10;
+        for key in (scan..scan + 10).chain(0..5) {
+            if cache.get(&key).is_some() {
+                hits += 1;
+            } else {
+                cache.put(key, key);
+            }
+            if lru.get(&key).is_some() {
+                lru_hits += 1;
+            } else {
+                lru.put(key, key);
+            }
+        }
+    }
+    assert_eq!(lru_hits, 0);
+    assert!(hits >= 40);
+}
+
+#[test]
+fn test_slru_get_promote_to_protected() {
+    let mut cache = SlruCache::<usize, &str>::new(4, 60);
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    assert_eq!(cache.policy().sizes(), (2, 0));
+
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.policy().sizes(), (1, 1));
+
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.policy().sizes(), (1, 1));
+}
+
+#[test]
+fn test_slru_bounded_demote_protected_overflow() {
+    let mut cache = SlruCache::<usize, &str>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .policy(Slru::with_protected_percent(34))
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), Some(&"two"));
+    assert_eq!(cache.policy().sizes(), (2, 1));
+
+    // 1 is demoted to the front of probationary segment
+    assert_eq!(cache.push(4, "four"), Some((3, "three")));
+    assert_eq!(cache.push(5, "five"), Some((1, "one")));
+    assert_eq!(cache.get(&2), Some(&"two"));
+}
+
+#[test]
+#[should_panic(expected = "Protected segment defined with more than 100 percent")]
+fn test_slru_protected_percent_over_100() {
+    Slru::with_protected_percent(101);
+}
+
+#[test]
+fn test_slru_bounded_scan_resistant() {
+    let mut cache = SlruCache::<usize, usize>::builder()
+        .max_capacity(4)
+        .timeout_secs(60)
+        .build();
+    let mut lru = LruCache::<usize, usize>::builder()
+        .max_capacity(4)
+        .timeout_secs(60)
+        .build();
+
+    // hot keys 0 and 1 accessed twice, then interleaved with one-time scan
+    for key in (0..2).chain(0..2) {
+        if cache.get(&key).is_none() {
+            cache.put(key, key);
+        }
+        if lru.get(&key).is_none() {
+            lru.put(key, key);
+        }
+    }
+    let (mut hits, mut lru_hits) = (0, 0);
+    for round in 0..10 {
+        let scan = 100 + round * 4;
+        for key in (scan..scan + 4).chain(0..2) {
+            if cache.get(&key).is_some() {
+                hits += 1;
+            } else {
+                cache.put(key, key);
+            }
+            if lru.get(&key).is_some() {
+                lru_hits += 1;
+            } else {
+                lru.put(key, key);
+            }
+        }
+    }
+    assert_eq!(lru_hits, 0);
+    assert_eq!(hits, 20);
+}
+
+#[test]
+fn test_clock_bounded_second_chance() {
+    let mut cache = ClockCache::<usize, &str>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.get(&2), Some(&"two"));
+
+    assert_eq!(cache.push(4, "four"), Some((3, "three")));
+    // reference bits of 1 and 2 are already cleared
+    assert_eq!(cache.push(5, "five"), Some((1, "one")));
+    assert_eq!(cache.put(2, "deux"), Some("two"));
+    assert_eq!(cache.push(6, "six"), Some((4, "four")));
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache.get(&2), Some(&"deux"));
+    assert_eq!(cache.get(&5), Some(&"five"));
+    assert_eq!(cache.get(&6), Some(&"six"));
+}
+
+#[test]
+fn test_clock_bounded_all_referenced() {
+    let mut cache = ClockCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    assert_eq!(cache.get(&2), Some(&"two"));
+    assert_eq!(cache.get(&1), Some(&"one"));
+
+    // a full sweep clears every bit, then the oldest entry is evicted
+    assert_eq!(cache.push(3, "three"), Some((1, "one")));
+}
+
+#[test]
+fn test_s3_fifo_bounded_ghost_insert_main() {
+    let mut cache = S3FifoCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+
+    for key in 0..10 {
+        cache.put(key, key);
+    }
+    assert_eq!(cache.get(&0), Some(&0));
+    assert_eq!(cache.policy().sizes(), (10, 0, 0));
+
+    // 0 is read again, moved into main
+    assert_eq!(cache.push(10, 10), Some((1, 1)));
+    assert_eq!(cache.policy().sizes(), (9, 1, 1));
+
+    // 1 is remembered by ghost, inserted into main
+    assert_eq!(cache.push(1, 1), Some((2, 2)));
+    assert_eq!(cache.policy().sizes(), (8, 2, 1));
+    assert_eq!(cache.get(&0), Some(&0));
+    assert_eq!(cache.get(&1), Some(&1));
+}
+
+#[test]
+fn test_s3_fifo_bounded_reinsert_main() {
+    let mut cache = S3FifoCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+
+    for key in 0..10 {
+        cache.put(key, key);
+        cache.get(&key);
+    }
+    // every entry is moved into main, then the oldest is evicted
+    // after every entry is reinserted once
+    assert_eq!(cache.push(10, 10), Some((0, 0)));
+    assert_eq!(cache.policy().sizes(), (1, 9, 0));
+}
+
+#[test]
+fn test_s3_fifo_bounded_scan_compared_to_lru() {
+    let mut cache = S3FifoCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+    let mut lru = LruCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+
+    // hot keys accessed twice, then interleaved with one-time scan
+    for key in (0..5).chain(0..5) {
+        if cache.get(&key).is_none() {
+            cache.put(key, key);
+        }
+        if lru.get(&key).is_none() {
+            lru.put(key, key);
+        }
+    }
+    let (mut hits, mut lru_hits) = (0, 0);
+    for round in 0..10 {
+        let scan = 100 + round * 10;
+        for key in (scan..scan + 10).chain(0..5) {
+            if cache.get(&key).is_some() {
+                hits += 1;
+            } else {
+                cache.put(key, key);
+            }
+            if lru.get(&key).is_some() {
+                lru_hits += 1;
+            } else {
+                lru.put(key, key);
+            }
+        }
+    }
+    assert_eq!(lru_hits, 0);
+    assert_eq!(hits, 50);
+}
+
+#[test]
+fn test_s3_fifo_bounded_long_scan_compared_to_lru() {
+    let mut cache = S3FifoCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+    let mut lru = LruCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+
+    // working set fitting the cache, repeated before and after long scan
+    let (mut hits, mut lru_hits) = (0, 0);
+    let sequence = (0..8).chain(0..8).chain(100..200).chain(0..8).chain(0..8);
+    for key in sequence {
+        if cache.get(&key).is_some() {
+            hits += 1;
+        } else {
+            cache.put(key, key);
+        }
+        if lru.get(&key).is_some() {
+            lru_hits += 1;
+        } else {
+            lru.put(key, key);
+        }
+    }
+    assert_eq!(lru_hits, 16);
+    assert!(hits > lru_hits);
+}
+
+#[test]
+fn test_lirs_bounded_hir_reuse_promote_to_lir() {
+    let mut cache = LirsCache::<usize, &str>::builder()
+        .max_capacity(4)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.put(3, "three");
+    cache.put(4, "four");
+    assert_eq!(cache.policy().sizes(), (3, 1, 4));
+
+    // evicted HIR key stays on the stack
+    assert_eq!(cache.push(5, "five"), Some((4, "four")));
+    assert_eq!(cache.policy().sizes(), (3, 1, 5));
+
+    // seen again while on the stack, bottom LIR entry is demoted
+    assert_eq!(cache.push(4, "quatre"), Some((5, "five")));
+    assert_eq!(cache.policy().sizes(), (3, 1, 4));
+    assert_eq!(cache.push(6, "six"), Some((1, "one")));
+    assert_eq!(cache.get(&4), Some(&"quatre"));
+}
+
+#[test]
+fn test_lirs_bounded_loop_compared_to_lru() {
+    let mut cache = LirsCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+    let mut lru = LruCache::<usize, usize>::builder()
+        .max_capacity(10)
+        .timeout_secs(60)
+        .build();
+
+    // loop over slightly more keys than fit in the cache
+    let (mut hits, mut lru_hits) = (0, 0);
+    for _ in 0..10 {
+        for key in 0..11 {
+            if cache.get(&key).is_some() {
+                hits += 1;
+            } else {
+                cache.put(key, key);
+            }
+            if lru.get(&key).is_some() {
+                lru_hits += 1;
+            } else {
+                lru.put(key, key);
+            }
+        }
+    }
+    assert_eq!(lru_hits, 0);
+    assert_eq!(hits, 81);
+}
+
+#[test]
+fn test_lirs_bounded_capacity_1() {
+    let mut cache = LirsCache::<usize, &str>::builder()
+        .max_capacity(1)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(2, "two");
+    assert_eq!(cache.push(1, "one"), Some((2, "two")));
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.push(2, "deux"), Some((1, "one")));
+    assert_eq!(cache.get(&2), Some(&"deux"));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_lirs_bounded_small_capacity() {
+    let mut cache = LirsCache::<usize, usize>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, 1);
+    cache.put(2, 2);
+    assert_eq!(cache.policy().sizes(), (1, 1, 2));
+
+    // HIR entry seen again while on the stack swaps with the bottom LIR entry
+    assert_eq!(cache.get(&2), Some(&2));
+    assert_eq!(cache.policy().sizes(), (1, 1, 1));
+    assert_eq!(cache.push(3, 3), Some((1, 1)));
+    assert_eq!(cache.push(1, 1), Some((3, 3)));
+    assert_eq!(cache.get(&3), None);
+    assert_eq!(cache.get(&1), Some(&1));
+    assert_eq!(cache.get(&2), Some(&2));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn test_lirs_bounded_pruned_non_resident_stays_hir() {
+    let mut cache = LirsCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    assert_eq!(cache.push(3, "three"), Some((2, "two")));
+    assert_eq!(cache.policy().sizes(), (1, 1, 3));
+
+    // reading the bottom LIR entry prunes non-resident key off the stack
+    assert_eq!(cache.get(&1), Some(&"one"));
+    assert_eq!(cache.policy().sizes(), (1, 1, 1));
+
+    // no longer seen, the key comes back as HIR instead of taking over LIR
+    assert_eq!(cache.push(2, "deux"), Some((3, "three")));
+    assert_eq!(cache.policy().sizes(), (1, 1, 2));
+    assert_eq!(cache.push(4, "four"), Some((2, "deux")));
+    assert_eq!(cache.get(&1), Some(&"one"));
+}
+
+#[test]
+fn test_lirs_bounded_random_sequence() {
+    for capacity in &[1, 2, 3, 5, 16, 150] {
+        for seed in 0..200 {
+            random_sequence::<Lirs>(seed, *capacity);
+        }
+    }
+}
+
+#[test]
+fn test_gdsf_bounded_evict_lowest_priority() {
+    let mut cache = GdsfCache::<usize, (u64, usize)>::builder()
+        .max_capacity(3)
+        .timeout_secs(60)
+        .weigher(|_: &usize, &(cost, size): &(u64, usize)| Weight { cost, size })
+        .build();
+
+    cache.put(1, (1, 10));
+    cache.put(2, (10, 10));
+    cache.put(3, (1, 1));
+    assert_eq!(cache.policy().priorities(), (0.0, vec![0.1, 1.0, 1.0]));
+
+    // clock is raised to the priority of the victim
+    assert_eq!(cache.push(4, (1, 2)), Some((1, (1, 10))));
+    assert_eq!(cache.policy().priorities(), (0.1, vec![0.6, 1.0, 1.0]));
+
+    assert_eq!(cache.get(&4), Some(&(1, 2)));
+    assert_eq!(cache.policy().priorities(), (0.1, vec![1.0, 1.0, 1.1]));
+
+    // same priority, the least recently prioritized is evicted
+    assert_eq!(cache.push(5, (1, 1)), Some((2, (10, 10))));
+    assert_eq!(cache.policy().priorities(), (1.0, vec![1.0, 1.1, 2.0]));
+}
+
+#[test]
+fn test_gdsf_bounded_update_reweigh() {
+    let mut cache = GdsfCache::<usize, (u64, usize)>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .weigher(|_: &usize, &(cost, size): &(u64, usize)| Weight { cost, size })
+        .build();
+
+    cache.put(1, (1, 1));
+    cache.put(2, (1, 1));
+    assert_eq!(cache.put(1, (1, 4)), Some((1, 1)));
+    assert_eq!(cache.policy().priorities(), (0.0, vec![0.5, 1.0]));
+    assert_eq!(cache.push(3, (1, 1)), Some((1, (1, 4))));
+}
+
+#[test]
+fn test_gdsf_bounded_unit_weight_evict_least_frequently_used() {
+    let mut cache = GdsfCache::<usize, &str>::builder()
+        .max_capacity(2)
+        .timeout_secs(60)
+        .build();
+
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.get(&1);
+    assert_eq!(cache.push(3, "three"), Some((2, "two")));
+
+    // aged by the clock, new entry outranks the one used twice
+    assert_eq!(cache.policy().priorities(), (1.0, vec![2.0, 2.0]));
+    assert_eq!(cache.push(4, "four"), Some((1, "one")));
+}
+
+/// Run seeded random sequence of puts, reads, reweighing and expiry on a cache
+/// with given policy, checking it stays within its bounds.
+fn random_sequence<P: EvictionPolicy + Default>(seed: u64, capacity: usize) {
+    let clock = MockClock::new();
+    let mut cache = Cache::<u64, usize, P>::builder()
+        .max_capacity(capacity)
+        .max_weight(capacity * 2)
+        .weigher(|_: &u64, size: &usize| Weight {
+            cost: 1,
+            size: *size,
+        })
+        .timeout_secs(60)
+        .clock(clock.clone())
+        .build();
+
+    // xorshift64*
+    let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+    let mut random = move || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    };
+    for _ in 0..500 {
+        let number = random();
+        let key = (number >> 8) % (capacity as u64 * 3);
+        let size = (number >> 32) as usize % 3 + 1;
+        match number % 8 {
+            0..=2 => {
+                if cache.try_push(key, size).is_ok() {
+                    assert_eq!(cache.peek(&key), Some(&size));
+                }
+            }
+            3 => {
+                let ttl = Duration::from_millis((number >> 16) % 100);
+                cache.put_with_ttl(key, size, ttl);
+            }
+            4..=6 => {
+                cache.get(&key);
+            }
+            _ => {
+                clock.advance(Duration::from_millis((number >> 16) % 50));
+                cache.evict();
+            }
+        }
+        assert!(cache.len() <= capacity);
+        assert!(cache.weight() <= capacity * 2);
+    }
+}
+
+/// Generate tests every policy has to pass, each in a module named after the policy.
+macro_rules! policy_contract {
+    ($($name:ident: $policy:ty,)*) => {
+        $(
+            mod $name {
+                use super::*;
+
+                #[test]
+                fn test_bounded_push() {
+                    let mut cache = Cache::<usize, &str, $policy>::builder()
+                        .max_capacity(3)
+                        .timeout_secs(60)
+                        .build();
+
+                    assert_eq!(cache.push(1, "one"), None);
+                    assert_eq!(cache.push(2, "two"), None);
+                    assert_eq!(cache.push(3, "three"), None);
+                    assert_eq!(cache.get(&1), Some(&"one"));
+
+                    let (key, _) = cache.push(4, "four").unwrap();
+                    assert_eq!(cache.peek(&key), None);
+                    assert_eq!(cache.get(&4), Some(&"four"));
+                    assert_eq!(cache.len(), 3);
+                    assert_eq!(cache.capacity(), 3);
+                }
+
+                #[test]
+                fn test_get_expire_entry() {
+                    let clock = MockClock::new();
+                    let mut cache = Cache::<usize, &str, $policy>::builder()
+                        .multiply_cap(2)
+                        .expiry_mode(ExpiryMode::ExpireAfterWrite(Duration::from_secs(1)))
+                        .clock(clock.clone())
+                        .build();
+
+                    cache.put(1, "one");
+                    cache.put(2, "two");
+                    assert_eq!(cache.get(&2), Some(&"two"));
+
+                    clock.advance(Duration::from_secs(1));
+                    assert_eq!(cache.get(&2), None);
+
+                    cache.evict();
+                    assert_eq!(cache.len(), 0);
+                    assert_eq!(cache.capacity(), 0);
+
+                    // policy is left with nothing of the removed entries
+                    cache.put(1, "un");
+                    assert_eq!(cache.get(&1), Some(&"un"));
+                    assert_eq!(cache.len(), 1);
+                }
+
+                #[test]
+                fn test_bounded_random_sequence() {
+                    for capacity in &[1, 2, 5] {
+                        for seed in 0..20 {
+                            random_sequence::<$policy>(seed, *capacity);
+                        }
+                    }
+                }
+
+                #[cfg(feature = "asynchronous")]
+                #[tokio::test]
+                async fn test_bounded_push_async() {
+                    let cache = AsyncCache::<usize, &str, $policy>::builder()
+                        .max_capacity(3)
+                        .timeout_secs(60)
+                        .build_async();
+
+                    assert_eq!(cache.push(1, "one").await, None);
+                    assert_eq!(cache.push(2, "two").await, None);
+                    assert_eq!(cache.push(3, "three").await, None);
+                    assert_eq!(cache.get(&1).await, Some("one"));
+
+                    let (key, _) = cache.push(4, "four").await.unwrap();
+                    assert_eq!(cache.peek(&key).await, None);
+                    assert_eq!(cache.get(&4).await, Some("four"));
+                    assert_eq!(cache.len().await, 3);
+                    assert_eq!(cache.capacity().await, 3);
+                }
+            }
+        )*
+    };
+}
+
+policy_contract! {
+    lru: Lru,
+    lfu: Lfu,
+    mru: Mru,
+    fifo: Fifo,
+    clock: Clock,
+    s3_fifo: S3Fifo,
+    slru: Slru,
+    arc: Adaptive,
+    lirs: Lirs,
+    tiny_lfu: TinyLfu,
+    gdsf: Gdsf,
+}
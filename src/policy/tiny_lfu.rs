@@ -0,0 +1,150 @@
+use super::{sketch::CountMinSketch, Entries, EvictionPolicy, Handle, Link, List};
+
+/// W-TinyLFU, keeping one-hit-wonder keys from pushing out useful entries.
+///
+/// New entries are inserted into a small admission window LRU, taking 1%
+/// of the capacity. The entry leaving the window is admitted into the main
+/// segmented LRU only when its frequency, estimated by a count-min sketch,
+/// is higher than the frequency of main's victim. Main is split into
+/// probationary and protected segments, the latter taking 80% of it.
+#[derive(Debug, Default)]
+pub struct TinyLfu {
+    window: List,
+    probation: List,
+    protected: List,
+    sketch: CountMinSketch,
+
+    window_cap: usize,
+    protected_cap: usize,
+}
+
+/// Position of an entry within [`TinyLfu`](struct.TinyLfu.html) segments
+#[derive(Debug, Default)]
+pub struct TinyLfuMeta {
+    link: Link,
+    segment: Segment,
+    hash: u64,
+}
+
+impl AsMut<Link> for TinyLfuMeta {
+    fn as_mut(&mut self) -> &mut Link {
+        &mut self.link
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Segment {
+    Window,
+    Probation,
+    Protected,
+}
+
+impl Default for Segment {
+    /// New entry is admitted to the window first
+    fn default() -> Self {
+        Segment::Window
+    }
+}
+
+impl TinyLfu {
+    fn segment(&mut self, segment: Segment) -> &mut List {
+        match segment {
+            Segment::Window => &mut self.window,
+            Segment::Probation => &mut self.probation,
+            Segment::Protected => &mut self.protected,
+        }
+    }
+
+    /// Move the entry to the front of given segment.
+    fn transfer(&mut self, entries: &mut impl Entries<TinyLfuMeta>, handle: Handle, to: Segment) {
+        let from = entries[handle].segment;
+        self.segment(from).unlink(entries, handle);
+        self.segment(to).push_front(entries, handle);
+        entries[handle].segment = to;
+    }
+
+    /// Returns the least recently used entry of main.
+    fn main_victim(&self) -> Option<Handle> {
+        self.probation.back().or_else(|| self.protected.back())
+    }
+
+    /// Returns the number of entries of window, probation and protected segments.
+    #[cfg(test)]
+    pub(crate) fn sizes(&self) -> (usize, usize, usize) {
+        (
+            self.window.len(),
+            self.probation.len(),
+            self.protected.len(),
+        )
+    }
+}
+
+impl EvictionPolicy for TinyLfu {
+    type Meta = TinyLfuMeta;
+
+    fn on_init(&mut self, capacity: usize) {
+        self.window_cap = (capacity / 100).max(1);
+        self.protected_cap = capacity.saturating_sub(self.window_cap) * 4 / 5;
+        self.sketch = CountMinSketch::new(capacity);
+    }
+
+    /// Put the entry into window, moving the overflow into probation.
+    fn on_insert(&mut self, entries: &mut impl Entries<TinyLfuMeta>, handle: Handle, hash: u64) {
+        self.sketch.increment(hash);
+        entries[handle].hash = hash;
+        self.window.push_front(entries, handle);
+        while self.window.len() > self.window_cap {
+            if let Some(last) = self.window.back() {
+                self.transfer(entries, last, Segment::Probation);
+            }
+        }
+    }
+
+    /// Promote the entry from probation into protected, demoting
+    /// the overflow of protected back into probation.
+    fn on_access(&mut self, entries: &mut impl Entries<TinyLfuMeta>, handle: Handle) {
+        self.sketch.increment(entries[handle].hash);
+        match entries[handle].segment {
+            Segment::Window => self.window.move_to_front(entries, handle),
+            Segment::Protected => self.protected.move_to_front(entries, handle),
+            Segment::Probation => {
+                self.transfer(entries, handle, Segment::Protected);
+                if self.protected.len() > self.protected_cap {
+                    if let Some(last) = self.protected.back() {
+                        self.transfer(entries, last, Segment::Probation);
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_remove(&mut self, entries: &mut impl Entries<TinyLfuMeta>, handle: Handle) {
+        let segment = entries[handle].segment;
+        self.segment(segment).unlink(entries, handle);
+    }
+
+    /// Choose between the entry leaving window and main's victim,
+    /// admitting the former into probation when it's used more often.
+    fn victim(&mut self, entries: &mut impl Entries<TinyLfuMeta>, _: u64) -> Option<Handle> {
+        let candidate = if self.window.len() >= self.window_cap {
+            self.window.back()
+        } else {
+            None
+        };
+        let handle = match (candidate, self.main_victim()) {
+            (Some(candidate), Some(victim)) => {
+                let admit = self.sketch.frequency(entries[candidate].hash)
+                    > self.sketch.frequency(entries[victim].hash);
+                if admit {
+                    self.transfer(entries, candidate, Segment::Probation);
+                    victim
+                } else {
+                    candidate
+                }
+            }
+            (candidate, victim) => candidate.or(victim).or_else(|| self.window.back())?,
+        };
+        self.on_remove(entries, handle);
+        Some(handle)
+    }
+}